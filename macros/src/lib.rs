@@ -0,0 +1,220 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Fields, Ident, LitInt, LitStr, Result,
+    meta::ParseNestedMeta, parse_macro_input,
+};
+
+/// Implements `TransitionStates`, building a `TransitionRoutes` table from `#[transition(...)]`.
+///
+/// On the enum the attribute sets the default route, on a unit variant it styles every
+/// transition into that variant, or only the ones coming `from` another variant:
+///
+/// ```ignore
+/// #[derive(States, TransitionStates, Reflect, Clone, PartialEq, Eq, Hash, Debug, Default)]
+/// #[transition(effect = "fade", duration_ms = 400, color = "black")]
+/// enum GameState {
+///     #[default]
+///     Menu,
+///     #[transition(duration_ms = 1000)]
+///     #[transition(from = Paused, effect = "wipe_left_to_right", duration_ms = 200)]
+///     Playing,
+///     #[transition(fade_out_ms = 0, color = "#00000080")]
+///     Paused,
+/// }
+/// ```
+///
+/// Supported options are `effect` (a name registered in `TransitionEffects`), `color` (a css
+/// colour name or a hex string), `easing` (an `EaseFunction` variant), `duration_ms` for both
+/// fades, and `fade_out_ms`, `hold_ms` and `fade_in_ms` individually.
+#[proc_macro_derive(TransitionStates, attributes(transition))]
+pub fn derive_transition_states(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "TransitionStates can only be derived for enums",
+        ));
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut routes = Vec::new();
+    for (index, attr) in transition_attrs(&input.attrs).enumerate() {
+        if index > 0 {
+            return Err(Error::new_spanned(
+                attr,
+                "the default transition is already declared",
+            ));
+        }
+
+        let route = Route::parse(attr)?;
+        if let Some(from) = route.from {
+            return Err(Error::new_spanned(from, "`from` only applies to variants"));
+        }
+
+        let options = route.options();
+        routes.push(quote! { routes.set_default(Some(#options)); });
+    }
+
+    for variant in &data.variants {
+        let attrs = transition_attrs(&variant.attrs).collect::<Vec<_>>();
+        if attrs.is_empty() {
+            continue;
+        }
+
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "transitions can only be declared on unit variants",
+            ));
+        }
+
+        let to = &variant.ident;
+        for attr in attrs {
+            let route = Route::parse(attr)?;
+            let options = route.options();
+            let from = match route.from {
+                Some(from) => quote! { Some(#name::#from) },
+                None => quote! { None },
+            };
+
+            routes.push(quote! { routes.insert(#from, Some(#name::#to), #options); });
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::bevy_transitions::TransitionStates for #name #ty_generics #where_clause {
+            fn transition_routes() -> ::bevy_transitions::TransitionRoutes<Self> {
+                let mut routes = ::bevy_transitions::TransitionRoutes::default();
+                #(#routes)*
+                routes
+            }
+        }
+    })
+}
+
+fn transition_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("transition"))
+}
+
+#[derive(Default)]
+struct Route {
+    from: Option<Ident>,
+    effect: Option<TokenStream2>,
+    color: Option<TokenStream2>,
+    easing: Option<TokenStream2>,
+    duration: Option<TokenStream2>,
+    fade_out: Option<TokenStream2>,
+    hold: Option<TokenStream2>,
+    fade_in: Option<TokenStream2>,
+}
+
+impl Route {
+    fn parse(attr: &Attribute) -> Result<Self> {
+        let mut route = Self::default();
+        attr.parse_nested_meta(|meta| {
+            let Some(key) = meta.path.get_ident().map(Ident::to_string) else {
+                return Err(meta.error("expected a transition option"));
+            };
+
+            match key.as_str() {
+                "from" => route.from = Some(meta.value()?.parse()?),
+                "effect" => {
+                    let name = meta.value()?.parse::<LitStr>()?;
+                    route.effect = Some(quote! { ::bevy_transitions::SharedEffect::named(#name) });
+                }
+                "color" => route.color = Some(parse_color(&meta.value()?.parse()?)?),
+                "easing" => {
+                    let easing = meta.value()?.parse::<LitStr>()?;
+                    let easing = easing.parse::<Ident>()?;
+                    route.easing = Some(quote! {
+                        ::bevy_transitions::TransitionCurve::from(
+                            ::bevy_transitions::__macro::EaseFunction::#easing
+                        )
+                    });
+                }
+                "duration_ms" => route.duration = Some(parse_millis(&meta)?),
+                "fade_out_ms" => route.fade_out = Some(parse_millis(&meta)?),
+                "hold_ms" => route.hold = Some(parse_millis(&meta)?),
+                "fade_in_ms" => route.fade_in = Some(parse_millis(&meta)?),
+                _ => return Err(meta.error(format!("unknown transition option `{key}`"))),
+            }
+
+            Ok(())
+        })?;
+
+        Ok(route)
+    }
+
+    fn options(&self) -> TokenStream2 {
+        let fields = [
+            ("effect", self.effect.as_ref()),
+            ("color", self.color.as_ref()),
+            ("easing", self.easing.as_ref()),
+            ("fade_out", self.fade_out.as_ref()),
+            ("hold", self.hold.as_ref()),
+            ("fade_in", self.fade_in.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(field, value)| {
+            let field = Ident::new(field, proc_macro2::Span::call_site());
+            value.map(|value| quote! { #field: Some(#value) })
+        });
+
+        let duration = self
+            .duration
+            .as_ref()
+            .map(|duration| quote! { .or_duration(#duration) });
+
+        quote! {
+            ::bevy_transitions::TransitionOptions {
+                #(#fields,)*
+                ..::core::default::Default::default()
+            }
+            #duration
+        }
+    }
+}
+
+fn parse_millis(meta: &ParseNestedMeta) -> Result<TokenStream2> {
+    let millis = meta.value()?.parse::<LitInt>()?.base10_parse::<u64>()?;
+    Ok(quote! { ::bevy_transitions::__macro::Duration::from_millis(#millis) })
+}
+
+fn parse_color(color: &LitStr) -> Result<TokenStream2> {
+    let value = color.value();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        if !matches!(hex.len(), 3 | 4 | 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::new_spanned(
+                color,
+                "expected a colour like `#rgb` or `#rrggbbaa`",
+            ));
+        }
+
+        return Ok(quote! {
+            ::bevy_transitions::__macro::Color::Srgba(
+                ::bevy_transitions::__macro::Srgba::hex(#color).unwrap()
+            )
+        });
+    }
+
+    // unknown names surface as a missing constant pointing at the attribute
+    let mut name = syn::parse_str::<Ident>(&value.to_uppercase())
+        .map_err(|_| Error::new_spanned(color, "expected a css colour name or a hex colour"))?;
+    name.set_span(color.span());
+    Ok(quote! {
+        ::bevy_transitions::__macro::Color::from(::bevy_transitions::__macro::css::#name)
+    })
+}
@@ -0,0 +1,302 @@
+use crate::TransitionPhase;
+use bevy_camera::prelude::*;
+use bevy_color::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+use bevy_reflect::prelude::*;
+use bevy_transform::prelude::*;
+use bevy_ui::prelude::*;
+use std::{collections::HashMap, fmt, sync::Arc};
+
+// in vmax, the outline has to reach every corner of the viewport from any center on screen
+const IRIS_OUTLINE_WIDTH: f32 = 200.0;
+const IRIS_OPEN_RADIUS: f32 = 150.0;
+
+/// The visuals of a transition, rendered into a full screen node under each camera's overlay.
+///
+/// The plugin owns the timing and state switching, an effect only draws `coverage` which goes
+/// from 0 (clear) to 1 (covered) and back, already eased.
+pub trait TransitionEffect: Send + Sync + 'static {
+    /// Called once per overlay when the effect becomes active, `content` is an empty full screen
+    /// node parented to the overlay.
+    fn spawn(&self, content: &mut EntityWorldMut);
+
+    /// Called every frame with the same content entity passed to [`Self::spawn`].
+    fn update(&self, content: &mut EntityWorldMut, context: &EffectContext);
+
+    /// Called before the content is despawned, because another effect took over or because the
+    /// overlay or its camera was despawned.
+    fn cleanup(&self, _content: &mut EntityWorldMut) {}
+}
+
+pub struct EffectContext {
+    pub coverage: f32,
+    pub phase: TransitionPhase,
+    pub color: Color,
+    pub camera: Entity,
+}
+
+impl EffectContext {
+    pub fn is_revealing(&self) -> bool {
+        matches!(
+            self.phase,
+            TransitionPhase::Revealing | TransitionPhase::Idle
+        )
+    }
+}
+
+/// A shareable [`TransitionEffect`], or the name of one registered in [`TransitionEffects`]
+/// which is looked up when the transition renders.
+#[derive(Clone)]
+pub struct SharedEffect(EffectSource);
+
+#[derive(Clone)]
+enum EffectSource {
+    Effect(Arc<dyn TransitionEffect>),
+    Named(Arc<str>),
+}
+
+impl SharedEffect {
+    pub fn new(effect: impl TransitionEffect) -> Self {
+        Self(EffectSource::Effect(Arc::new(effect)))
+    }
+
+    pub fn named(name: impl Into<Arc<str>>) -> Self {
+        Self(EffectSource::Named(name.into()))
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        match &self.0 {
+            EffectSource::Effect(_) => None,
+            EffectSource::Named(name) => Some(name),
+        }
+    }
+
+    pub fn resolve(
+        &self,
+        effects: Option<&TransitionEffects>,
+    ) -> Option<Arc<dyn TransitionEffect>> {
+        match &self.0 {
+            EffectSource::Effect(effect) => Some(effect.clone()),
+            EffectSource::Named(name) => match &effects?.get(name)?.0 {
+                EffectSource::Effect(effect) => Some(effect.clone()),
+                EffectSource::Named(_) => None,
+            },
+        }
+    }
+}
+
+impl fmt::Debug for SharedEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            EffectSource::Effect(_) => f.debug_tuple("SharedEffect").finish_non_exhaustive(),
+            EffectSource::Named(name) => f.debug_tuple("SharedEffect").field(name).finish(),
+        }
+    }
+}
+
+impl Default for SharedEffect {
+    fn default() -> Self {
+        Self::new(Fade)
+    }
+}
+
+impl<E> From<E> for SharedEffect
+where
+    E: TransitionEffect,
+{
+    fn from(effect: E) -> Self {
+        Self::new(effect)
+    }
+}
+
+/// Effects looked up by name, the built in ones are registered by default.
+#[derive(Resource, Clone, Debug)]
+pub struct TransitionEffects(HashMap<String, SharedEffect>);
+
+impl TransitionEffects {
+    pub fn get(&self, name: &str) -> Option<&SharedEffect> {
+        self.0.get(name)
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, effect: impl Into<SharedEffect>) {
+        self.0.insert(name.into(), effect.into());
+    }
+}
+
+impl Default for TransitionEffects {
+    fn default() -> Self {
+        let mut effects = Self(Default::default());
+        effects.insert("fade", Fade);
+        effects.insert("wipe_left_to_right", Wipe(WipeDirection::LeftToRight));
+        effects.insert("wipe_right_to_left", Wipe(WipeDirection::RightToLeft));
+        effects.insert("wipe_top_to_bottom", Wipe(WipeDirection::TopToBottom));
+        effects.insert("wipe_bottom_to_top", Wipe(WipeDirection::BottomToTop));
+        effects.insert("wipe_diagonal", Wipe(WipeDirection::Diagonal));
+        effects.insert("iris", Iris::default());
+        effects.insert("iris_target", Iris(IrisCenter::Target));
+        effects
+    }
+}
+
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Fade;
+
+impl TransitionEffect for Fade {
+    fn spawn(&self, content: &mut EntityWorldMut) {
+        content.insert(BackgroundColor(Color::NONE));
+    }
+
+    fn update(&self, content: &mut EntityWorldMut, context: &EffectContext) {
+        let color = context.color;
+        if let Some(mut background) = content.get_mut::<BackgroundColor>() {
+            background.0 = color.with_alpha(color.alpha() * context.coverage);
+        }
+    }
+}
+
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Wipe(pub WipeDirection);
+
+/// The edge a wipe starts from, the reveal keeps moving the same way the cover did.
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WipeDirection {
+    LeftToRight,
+    RightToLeft,
+    TopToBottom,
+    BottomToTop,
+    /// A corner wipe, a rectangle grows out of the top left corner and recedes into the bottom
+    /// right one. Its sides follow the square root of the coverage so the covered area, not the
+    /// side length, follows the easing.
+    Diagonal,
+}
+
+impl TransitionEffect for Wipe {
+    fn spawn(&self, content: &mut EntityWorldMut) {
+        content.insert(BackgroundColor(Color::NONE));
+    }
+
+    fn update(&self, content: &mut EntityWorldMut, context: &EffectContext) {
+        // a corner wipe covers the square of its side
+        let side = match self.0 {
+            WipeDirection::Diagonal => context.coverage.sqrt(),
+            _ => context.coverage,
+        };
+
+        // the leading edge moves while covering, the trailing edge while revealing
+        let (lead, trail) = if context.is_revealing() {
+            (0.0, 1.0 - side)
+        } else {
+            (1.0 - side, 0.0)
+        };
+
+        let (left, top, width, height) = match self.0 {
+            WipeDirection::LeftToRight => (trail, 0.0, side, 1.0),
+            WipeDirection::RightToLeft => (lead, 0.0, side, 1.0),
+            WipeDirection::TopToBottom => (0.0, trail, 1.0, side),
+            WipeDirection::BottomToTop => (0.0, lead, 1.0, side),
+            WipeDirection::Diagonal => (trail, trail, side, side),
+        };
+
+        if let Some(mut background) = content.get_mut::<BackgroundColor>() {
+            background.set_if_neq(BackgroundColor(context.color));
+        }
+
+        update_node(content, |node| {
+            node.left = percent(left * 100.0);
+            node.top = percent(top * 100.0);
+            node.width = percent(width * 100.0);
+            node.height = percent(height * 100.0);
+        });
+    }
+}
+
+/// Closes a circle down to a point and reopens it once the state has changed.
+#[derive(Reflect, Clone, Copy, PartialEq, Debug, Default)]
+pub struct Iris(pub IrisCenter);
+
+#[derive(Reflect, Clone, Copy, PartialEq, Debug)]
+pub enum IrisCenter {
+    /// A point on screen, normalized from `(0, 0)` at the top left to `(1, 1)` at the bottom right.
+    Screen(Vec2),
+    /// The entity with an [`IrisTarget`] as seen by each overlay's camera, the middle of the
+    /// screen when there is none.
+    Target,
+}
+
+impl Default for IrisCenter {
+    fn default() -> Self {
+        Self::Screen(Vec2::splat(0.5))
+    }
+}
+
+/// Marks the entity an [`IrisCenter::Target`] iris closes onto, such as the player.
+#[derive(Component, Default)]
+pub struct IrisTarget;
+
+impl Iris {
+    fn center(&self, content: &mut EntityWorldMut, camera: Entity) -> Vec2 {
+        match self.0 {
+            IrisCenter::Screen(center) => center,
+            IrisCenter::Target => content
+                .world_scope(|world| {
+                    let target = world
+                        .query_filtered::<&GlobalTransform, With<IrisTarget>>()
+                        .iter(world)
+                        .next()?
+                        .translation();
+                    let (camera, transform) = world
+                        .query::<(&Camera, &GlobalTransform)>()
+                        .get(world, camera)
+                        .ok()?;
+                    let position = camera.world_to_viewport(transform, target).ok()?;
+                    Some(position / camera.logical_viewport_size()?)
+                })
+                .unwrap_or(Vec2::splat(0.5)),
+        }
+    }
+}
+
+impl TransitionEffect for Iris {
+    fn spawn(&self, content: &mut EntityWorldMut) {
+        // a transparent circle whose outline covers everything outside of it
+        content.insert((BackgroundColor(Color::NONE), Outline::default()));
+        if let Some(mut node) = content.get_mut::<Node>() {
+            node.border_radius = BorderRadius::MAX;
+        }
+    }
+
+    fn update(&self, content: &mut EntityWorldMut, context: &EffectContext) {
+        let center = self.center(content, context.camera);
+        let radius = (1.0 - context.coverage) * IRIS_OPEN_RADIUS;
+
+        update_node(content, |node| {
+            node.left = percent(center.x * 100.0);
+            node.top = percent(center.y * 100.0);
+            node.width = vmax(radius * 2.0);
+            node.height = vmax(radius * 2.0);
+            node.margin = UiRect {
+                left: vmax(-radius),
+                top: vmax(-radius),
+                ..Default::default()
+            };
+        });
+
+        if let Some(mut outline) = content.get_mut::<Outline>() {
+            outline.set_if_neq(Outline::new(
+                vmax(IRIS_OUTLINE_WIDTH),
+                px(0.0),
+                context.color,
+            ));
+        }
+    }
+}
+
+// rewriting a node with the same values would still mark it changed and relayout the ui
+fn update_node(content: &mut EntityWorldMut, update: impl FnOnce(&mut Node)) {
+    if let Some(mut node) = content.get_mut::<Node>() {
+        let mut updated = node.clone();
+        update(&mut updated);
+        node.set_if_neq(updated);
+    }
+}
@@ -1,18 +1,58 @@
 use bevy_app::prelude::*;
+use bevy_asset::{AssetPath, RecursiveDependencyLoadState, prelude::*};
 use bevy_color::prelude::*;
-use bevy_ecs::{prelude::*, system::SystemParam};
+use bevy_ecs::{
+    prelude::*,
+    query::QueryData,
+    schedule::{InternedScheduleLabel, ScheduleLabel},
+    system::{SystemId, SystemParam},
+};
+use bevy_log::warn;
+use bevy_math::prelude::*;
 use bevy_picking::Pickable;
 use bevy_reflect::{Reflectable, prelude::*};
-use bevy_state::{prelude::*, state::FreelyMutableState};
+use bevy_state::{
+    prelude::*,
+    state::{FreelyMutableState, StateTransition, StateTransitionEvent},
+};
+use bevy_text::TextColor;
 use bevy_time::prelude::*;
 use bevy_ui::{FocusPolicy, InteractionDisabled, prelude::*};
 use derive_new::new;
-use std::marker::PhantomData;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    marker::PhantomData,
+    sync::Arc,
+    time::Duration,
+};
 
-pub struct TransitionsPlugin<S, C>(PhantomData<(S, C)>)
+mod effect;
+mod profile;
+#[cfg(test)]
+mod tests;
+
+pub use bevy_transitions_macros::TransitionStates;
+pub use effect::*;
+pub use profile::*;
+
+#[doc(hidden)]
+pub mod __macro {
+    pub use bevy_color::{Color, Srgba, palettes::css};
+    pub use bevy_math::curve::EaseFunction;
+    pub use std::time::Duration;
+}
+
+pub struct TransitionsPlugin<S, C>
 where
     S: FreelyMutableState + Reflectable,
-    C: Component;
+    C: Component,
+{
+    settings: TransitionSettings<S>,
+    routes: TransitionRoutes<S>,
+    profile: Option<AssetPath<'static>>,
+    schedule: InternedScheduleLabel,
+    _marker: PhantomData<C>,
+}
 
 impl<S, C> Plugin for TransitionsPlugin<S, C>
 where
@@ -20,13 +60,47 @@ where
     C: Component,
 {
     fn build(&self, app: &mut App) {
-        app.init_resource::<TransitionSpeed>()
+        app.insert_resource(self.settings.clone())
+            .insert_resource(self.routes.clone())
+            .insert_resource(TransitionStatus::<S>::starting(self.settings.startup))
             .init_resource::<PendingState<S>>()
+            .init_resource::<TransitionConditions<S>>()
+            .init_resource::<LoadingSet<S>>()
+            .init_resource::<TransitionEffects>()
             .add_message::<TransitionMessage<S>>()
+            .add_message::<TransitionStarted<S>>()
+            .add_message::<TransitionCovered<S>>()
+            .add_message::<TransitionRevealStarted<S>>()
+            .add_message::<TransitionFinished<S>>()
+            .add_message::<TransitionCancelled<S>>()
+            .add_message::<TransitionRedirected<S>>()
+            .add_message::<TransitionLoadFailed<S>>()
             .add_observer(Self::on_camera_change)
             .add_observer(Self::on_camera_despawn)
-            .add_systems(Update, Self::apply_fade)
-            .add_systems(FixedUpdate, Self::handle_transition_events);
+            .add_observer(Self::on_overlay_despawn)
+            .add_systems(
+                self.schedule,
+                (
+                    Self::track_state_transitions,
+                    Self::check_readiness,
+                    Self::apply_fade,
+                    Self::render_effects,
+                    Self::handle_transition_events,
+                    Self::apply_state_change.run_if(Self::state_change_requested),
+                    Self::update_loading_screens,
+                )
+                    .chain(),
+            );
+
+        if let Some(path) = &self.profile {
+            if !app.world().contains_resource::<Assets<TransitionProfile>>() {
+                app.init_asset::<TransitionProfile>()
+                    .init_asset_loader::<TransitionProfileLoader>();
+            }
+
+            let handle = app.world().resource::<AssetServer>().load(path.clone());
+            app.insert_resource(TransitionProfileHandle::<S>::new(handle));
+        }
     }
 }
 
@@ -36,7 +110,13 @@ where
     C: Component,
 {
     fn default() -> Self {
-        Self(Default::default())
+        Self {
+            settings: Default::default(),
+            routes: Default::default(),
+            profile: None,
+            schedule: Update.intern(),
+            _marker: Default::default(),
+        }
     }
 }
 
@@ -45,21 +125,469 @@ where
     S: FreelyMutableState + Reflectable + Clone,
     C: Component,
 {
+    /// The schedule the fade runs in, the state change is applied by running `StateTransition` in
+    /// the same run that the screen becomes covered.
+    ///
+    /// That runs the whole schedule, so every pending `NextState` is applied at that point, of
+    /// other state types and other plugin instances too, not only the one for `S`.
+    pub fn with_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = schedule.intern();
+        self
+    }
+
+    pub fn with_settings(mut self, settings: TransitionSettings<S>) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn with_routes(mut self, routes: TransitionRoutes<S>) -> Self {
+        self.routes = routes;
+        self
+    }
+
+    /// Reads styles and routes from a [`TransitionProfile`] file. Its routes take precedence over
+    /// the ones set in code that are as specific, its default only applies when neither has a
+    /// matching route. Requires the `AssetPlugin`.
+    pub fn with_profile(mut self, path: impl Into<AssetPath<'static>>) -> Self {
+        self.profile = Some(path.into());
+        self
+    }
+
+    pub fn with_durations(mut self, fade_out: Duration, fade_in: Duration) -> Self {
+        self.settings.fade_out = fade_out;
+        self.settings.fade_in = fade_in;
+        self
+    }
+
+    pub fn with_fade_out(mut self, fade_out: Duration) -> Self {
+        self.settings.fade_out = fade_out;
+        self
+    }
+
+    pub fn with_hold(mut self, hold: Duration) -> Self {
+        self.settings.hold = hold;
+        self
+    }
+
+    pub fn with_fade_in(mut self, fade_in: Duration) -> Self {
+        self.settings.fade_in = fade_in;
+        self
+    }
+
+    pub fn with_color(mut self, color: impl Into<Color>) -> Self {
+        self.settings.color = color.into();
+        self
+    }
+
+    pub fn with_effect(mut self, effect: impl Into<SharedEffect>) -> Self {
+        self.settings.effect = effect.into();
+        self
+    }
+
+    pub fn with_z_index(mut self, z_index: i32) -> Self {
+        self.settings.z_index = z_index;
+        self
+    }
+
+    pub fn with_policy(mut self, policy: TransitionPolicy) -> Self {
+        self.settings.policy = policy;
+        self
+    }
+
+    pub fn with_overlay_fallback(mut self, fallback: OverlayFallback) -> Self {
+        self.settings.overlay_fallback = fallback;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: TransitionClock) -> Self {
+        self.settings.clock = clock;
+        self
+    }
+
+    pub fn with_startup(mut self, startup: StartupPolicy) -> Self {
+        self.settings.startup = startup;
+        self
+    }
+
+    /// The state to fall back to when an asset in the [`LoadingSet`] fails to load.
+    pub fn with_error_state(mut self, error_state: S) -> Self {
+        self.settings.error_state = Some(error_state);
+        self
+    }
+
+    pub fn with_loading_screen(
+        mut self,
+        spawn: impl Fn(&mut ChildSpawnerCommands) + Send + Sync + 'static,
+    ) -> Self {
+        self.settings.loading_screen = Some(LoadingScreen::new(spawn));
+        self
+    }
+
+    pub fn with_easing(mut self, easing: impl Curve<f32> + Send + Sync + 'static) -> Self {
+        let curve = TransitionCurve::new(easing);
+        self.settings.fade_out_easing = curve.clone();
+        self.settings.fade_in_easing = curve;
+        self
+    }
+
+    pub fn with_fade_out_easing(mut self, easing: impl Curve<f32> + Send + Sync + 'static) -> Self {
+        self.settings.fade_out_easing = TransitionCurve::new(easing);
+        self
+    }
+
+    pub fn with_fade_in_easing(mut self, easing: impl Curve<f32> + Send + Sync + 'static) -> Self {
+        self.settings.fade_in_easing = TransitionCurve::new(easing);
+        self
+    }
+
     fn apply_fade(
-        mut q_overlays: Query<&mut BackgroundColor, With<FadeOverlay>>,
         mut transition: Transition<S>,
         time: Res<Time>,
+        real_time: Res<Time<Real>>,
+        virtual_time: Res<Time<Virtual>>,
+        q_overlays: Query<(), With<FadeOverlay<S>>>,
+    ) {
+        let delta = match transition.current_clock() {
+            TransitionClock::Time => time.delta(),
+            TransitionClock::Virtual => virtual_time.delta(),
+            TransitionClock::Real => real_time.delta(),
+            TransitionClock::Frames(step) => step,
+        };
+
+        // the timing doesn't depend on the overlays, without any the transition still completes
+        let headless = transition.status.is_transitioning() && q_overlays.is_empty();
+        if headless
+            && !transition.status.warned_headless
+            && let Some((from, to)) = &transition.status.route
+        {
+            warn!(
+                "transition from {from:?} to {to:?} is running without an overlay, \
+                 no camera has the marker component"
+            );
+            transition.status.warned_headless = true;
+        }
+
+        let instant =
+            headless && transition.settings.overlay_fallback == OverlayFallback::Immediate;
+        let duration = |duration| if instant { Duration::ZERO } else { duration };
+
+        match transition.status.phase {
+            TransitionPhase::Idle => {}
+            TransitionPhase::Covering => {
+                let progress = advance(
+                    transition.status.progress,
+                    delta,
+                    duration(transition.current_fade_out()),
+                );
+                transition.status.progress = progress;
+
+                if progress >= 1.0
+                    && let Some(pending) = transition.take_pending()
+                {
+                    transition.writer.write(TransitionMessage::new(pending));
+                    transition.emit(TransitionCovered::new);
+
+                    transition.status.phase = TransitionPhase::Covered;
+                    transition.status.held = Duration::ZERO;
+                    transition.status.state_applied = false;
+                    transition.status.ready = false;
+                    transition.status.loading_progress = 0.0;
+                }
+            }
+            TransitionPhase::Covered => {
+                transition.status.held += delta;
+
+                // never reveal before OnExit/OnEnter for the new state have run
+                if transition.status.state_applied
+                    && transition.status.ready
+                    && transition.status.held >= duration(transition.current_hold())
+                {
+                    transition.start_reveal();
+                }
+            }
+            TransitionPhase::Revealing => {
+                let progress = 1.0 - transition.status.progress;
+                let progress = advance(progress, delta, duration(transition.current_fade_in()));
+                transition.status.progress = 1.0 - progress;
+
+                if progress >= 1.0 {
+                    transition.status.phase = TransitionPhase::Idle;
+                    transition.emit(TransitionFinished::new);
+                    transition.status.route = None;
+                    transition.start_next();
+                }
+            }
+        }
+
+        // covering eases 0 -> 1 on the fade out curve, revealing eases 1 -> 0 on the fade in curve
+        let progress = transition.status.progress;
+        let alpha = match transition.status.phase {
+            TransitionPhase::Covering | TransitionPhase::Covered => {
+                transition.current_fade_out_easing().sample(progress)
+            }
+            TransitionPhase::Revealing | TransitionPhase::Idle => {
+                1.0 - transition.current_fade_in_easing().sample(1.0 - progress)
+            }
+        };
+
+        transition.status.alpha = alpha.clamp(0.0, 1.0);
+    }
+
+    fn render_effects(world: &mut World) {
+        let overlays = world
+            .query_filtered::<Entity, With<FadeOverlay<S>>>()
+            .iter(world)
+            .collect::<Vec<_>>();
+        Self::render_overlays(world, overlays);
+    }
+
+    // every overlay renders from the one status, so all cameras show the same frame of the effect
+    fn render_overlays(world: &mut World, overlays: impl IntoIterator<Item = Entity>) {
+        let status = world.resource::<TransitionStatus<S>>();
+        let settings = world.resource::<TransitionSettings<S>>();
+        let effects = world.get_resource::<TransitionEffects>();
+        let effect = status.options.effect.as_ref().unwrap_or(&settings.effect);
+        let effect = effect
+            .resolve(effects)
+            .or_else(|| settings.effect.resolve(effects));
+        let unknown = [status.options.effect.as_ref(), Some(&settings.effect)]
+            .into_iter()
+            .flatten()
+            .filter(|effect| effect.resolve(effects).is_none())
+            .filter_map(|effect| effect.name().map(String::from))
+            .collect::<Vec<_>>();
+        let coverage = status.alpha;
+        let phase = status.phase;
+        let color = status.options.color.unwrap_or(settings.color);
+
+        let mut warned = world.get_resource_or_init::<UnknownEffects>();
+        for name in unknown {
+            if warned.0.insert(name.clone()) {
+                warn!("no transition effect is registered as `{name}`");
+            }
+        }
+
+        for overlay in overlays {
+            let Some((camera, content)) = world
+                .get::<FadeOverlay<S>>(overlay)
+                .map(|overlay| (overlay.camera, overlay.content.clone()))
+            else {
+                continue;
+            };
+
+            // an unknown effect name keeps whatever is already rendering
+            let effect = match (&effect, &content) {
+                (Some(effect), _) => effect.clone(),
+                (None, Some((_, current))) => current.clone(),
+                (None, None) => Arc::new(Fade),
+            };
+
+            let content = match content {
+                Some((content, current)) if Arc::ptr_eq(&current, &effect) => content,
+                content => {
+                    if let Some((content, current)) = content
+                        && let Ok(mut entity) = world.get_entity_mut(content)
+                    {
+                        current.cleanup(&mut entity);
+                        entity.despawn();
+                    }
+
+                    let mut entity = world.spawn((
+                        Name::new("Transition Effect"),
+                        ChildOf(overlay),
+                        FocusPolicy::Pass,
+                        Pickable::IGNORE,
+                        Node {
+                            position_type: PositionType::Absolute,
+                            top: px(0.0),
+                            left: px(0.0),
+                            width: percent(100.0),
+                            height: percent(100.0),
+                            ..Default::default()
+                        },
+                    ));
+                    effect.spawn(&mut entity);
+
+                    let content = entity.id();
+                    if let Some(mut overlay) = world.get_mut::<FadeOverlay<S>>(overlay) {
+                        overlay.content = Some((content, effect.clone()));
+                    }
+                    content
+                }
+            };
+
+            let Ok(mut content) = world.get_entity_mut(content) else {
+                continue;
+            };
+
+            let context = EffectContext {
+                coverage,
+                phase,
+                color,
+                camera,
+            };
+            effect.update(&mut content, &context);
+        }
+    }
+
+    fn track_state_transitions(
+        mut events: MessageReader<StateTransitionEvent<S>>,
+        mut status: ResMut<TransitionStatus<S>>,
+    ) {
+        // a redirect or an error state can leave the previous destination's event behind,
+        // the startup cover has no destination so any state entered under it counts
+        let to = status.to().cloned();
+        let entered = events
+            .read()
+            .filter(|event| event.entered.is_some() && (to.is_none() || event.entered == to))
+            .count();
+
+        if entered > 0 && status.phase == TransitionPhase::Covered {
+            status.state_applied = true;
+        }
+    }
+
+    fn check_readiness(world: &mut World) {
+        let status = world.resource::<TransitionStatus<S>>();
+        if status.phase != TransitionPhase::Covered || !status.state_applied {
+            return;
+        }
+
+        // conditions that fail to run are treated as ready rather than holding forever
+        let conditions = world.resource::<TransitionConditions<S>>().0.clone();
+        let conditions_total = conditions.len();
+        let conditions_met = conditions
+            .into_iter()
+            .filter(|condition| world.run_system(*condition).unwrap_or(true))
+            .count();
+
+        let released = world
+            .query_filtered::<(), With<TransitionHold<S>>>()
+            .iter(world)
+            .next()
+            .is_none();
+
+        let (loaded, loading_total) = Self::check_loading(world);
+
+        // holds count as a single step since there is no telling how many more will be spawned
+        let done = conditions_met + loaded + usize::from(released);
+        let total = conditions_total + loading_total + 1;
+
+        let mut status = world.resource_mut::<TransitionStatus<S>>();
+        status.loading_progress = done as f32 / total as f32;
+        status.ready = done == total;
+    }
+
+    fn check_loading(world: &mut World) -> (usize, usize) {
+        let Some(asset_server) = world.get_resource::<AssetServer>() else {
+            return (0, 0);
+        };
+
+        let handles = &world.resource::<LoadingSet<S>>().handles;
+        let mut loaded = 0;
+        let mut failed = None;
+        for handle in handles {
+            // handles the server does not track, e.g. from `Assets::add`, are already available
+            match asset_server.get_recursive_dependency_load_state(handle) {
+                None | Some(RecursiveDependencyLoadState::Loaded) => loaded += 1,
+                Some(RecursiveDependencyLoadState::Failed(_)) => {
+                    failed = Some(handle.clone());
+                    break;
+                }
+                Some(_) => {}
+            }
+        }
+
+        let Some(handle) = failed else {
+            return (loaded, handles.len());
+        };
+
+        // without a fallback the failed asset will never load, so stop holding for it,
+        // with one the reveal waits for the error state to be applied instead
+        world.resource_mut::<LoadingSet<S>>().clear();
+
+        // the startup cover has no route, its assets belong to the initial state
+        let to = match world.resource::<TransitionStatus<S>>().to() {
+            Some(to) => to.clone(),
+            None => world.resource::<State<S>>().get().clone(),
+        };
+
+        let error_state = world
+            .resource::<TransitionSettings<S>>()
+            .error_state
+            .clone()
+            .filter(|error_state| *error_state != to);
+
+        if let Some(error_state) = &error_state {
+            let mut status = world.resource_mut::<TransitionStatus<S>>();
+            status.route = status
+                .route
+                .take()
+                .map(|(from, _)| (from, error_state.clone()));
+            status.state_applied = false;
+            world
+                .resource_mut::<NextState<S>>()
+                .set(error_state.clone());
+        }
+
+        let event = TransitionLoadFailed::new(to, handle, error_state);
+        world.write_message(event.clone());
+        world.trigger(event);
+
+        (0, 0)
+    }
+
+    fn update_loading_screens(
+        mut commands: Commands,
+        status: Res<TransitionStatus<S>>,
+        mut q_screens: Query<(Entity, &mut Node), With<LoadingScreenRoot<S>>>,
+        q_children: Query<&Children>,
+        mut q_content: Query<LoadingScreenContent, Without<LoadingScreenRoot<S>>>,
     ) {
-        for mut overlay in &mut q_overlays {
-            let alpha =
-                (overlay.0.alpha() + transition.speed() * time.delta_secs()).clamp(0.0, 1.0);
-            overlay.0.set_alpha(alpha);
+        let display = loading_screen_display(status.phase);
+
+        for (screen, mut node) in &mut q_screens {
+            // writing the same value would still mark the node changed and relayout the ui
+            if node.display != display {
+                node.display = display;
+            }
+            if display == Display::None {
+                continue;
+            }
+
+            for entity in q_children.iter_descendants(screen) {
+                let Ok(content) = q_content.get_mut(entity) else {
+                    continue;
+                };
+
+                let Some(base) = content.base else {
+                    commands.entity(entity).insert(LoadingScreenAlpha {
+                        background: content.background.map(|color| color.0.alpha()),
+                        text: content.text.map(|color| color.0.alpha()),
+                        image: content.image.map(|image| image.color.alpha()),
+                    });
+                    continue;
+                };
+
+                if let (Some(mut color), Some(alpha)) = (content.background, base.background) {
+                    color.0.set_alpha(alpha * status.alpha);
+                }
+
+                if let (Some(mut color), Some(alpha)) = (content.text, base.text) {
+                    color.0.set_alpha(alpha * status.alpha);
+                }
+
+                if let (Some(mut image), Some(alpha)) = (content.image, base.image) {
+                    image.color.set_alpha(alpha * status.alpha);
+                }
 
-            if alpha >= 1.0
-                && let Some(pending) = transition.take_pending()
-            {
-                transition.writer.write(TransitionMessage::new(pending));
-                transition.set_speed(-transition.speed().abs());
+                let width = percent(status.loading_progress * 100.0);
+                if content.progress_bar
+                    && let Some(mut node) = content.node
+                    && node.width != width
+                {
+                    node.width = width;
+                }
             }
         }
     }
@@ -73,17 +601,27 @@ where
         }
     }
 
-    fn on_camera_change(event: On<Add, C>, mut commands: Commands) {
-        commands.spawn((
+    fn state_change_requested(next_state: Res<NextState<S>>) -> bool {
+        !matches!(*next_state, NextState::Unchanged)
+    }
+
+    // OnExit/OnEnter run while the screen is covered instead of waiting for the next frame,
+    // this applies the NextState of every state type, see `with_schedule`
+    fn apply_state_change(world: &mut World) {
+        let _ = world.try_run_schedule(StateTransition);
+    }
+
+    fn on_camera_change(event: On<Add, C>, mut commands: Commands, transition: Transition<S>) {
+        let camera = event.event_target();
+        let mut overlay = commands.spawn((
             Name::new("Fade Overlay"),
-            FadeOverlay,
-            BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 1.0)),
-            UiTargetCamera(event.event_target()),
-            OverlayOf(event.event_target()),
+            FadeOverlay::<S>::new(camera),
+            UiTargetCamera(camera),
+            OverlayOf(camera),
             FocusPolicy::Pass,
             InteractionDisabled,
             Pickable::IGNORE,
-            GlobalZIndex(i32::MAX),
+            GlobalZIndex(transition.settings().z_index),
             Node {
                 position_type: PositionType::Absolute,
                 top: px(0.0),
@@ -93,21 +631,71 @@ where
                 ..Default::default()
             },
         ));
+
+        if let Some(loading_screen) = transition.settings().loading_screen.clone() {
+            overlay.with_children(|parent| {
+                parent
+                    .spawn((
+                        Name::new("Loading Screen"),
+                        LoadingScreenRoot::<S>::default(),
+                        ZIndex(1),
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: percent(100.0),
+                            height: percent(100.0),
+                            display: loading_screen_display(transition.status().phase()),
+                            ..Default::default()
+                        },
+                    ))
+                    .with_children(|parent| (loading_screen.0)(parent));
+            });
+        }
+
+        // a camera joining mid transition starts at the current coverage instead of a frame late
+        let overlay = overlay.id();
+        commands.queue(move |world: &mut World| Self::render_overlays(world, [overlay]));
     }
 
     fn on_camera_despawn(
         event: On<Remove, Overlays>,
         mut commands: Commands,
         q_overlays: Query<&Overlays>,
+        q_owned: Query<(), With<FadeOverlay<S>>>,
     ) {
         let Ok(overlays) = q_overlays.get(event.event_target()) else {
             return;
         };
 
-        for entity in &overlays.0 {
+        // every plugin instance observes this, only despawn the overlays this state type owns
+        for entity in overlays
+            .0
+            .iter()
+            .filter(|entity| q_owned.contains(**entity))
+        {
             commands.entity(*entity).despawn();
         }
     }
+
+    fn on_overlay_despawn(
+        event: On<Despawn, FadeOverlay<S>>,
+        mut commands: Commands,
+        q_overlays: Query<&FadeOverlay<S>>,
+    ) {
+        let Some((content, current)) = q_overlays
+            .get(event.event_target())
+            .ok()
+            .and_then(|overlay| overlay.content.clone())
+        else {
+            return;
+        };
+
+        // despawn observers run before the children are despawned, so this is queued first
+        commands.queue(move |world: &mut World| {
+            if let Ok(mut entity) = world.get_entity_mut(content) {
+                current.cleanup(&mut entity);
+            }
+        });
+    }
 }
 
 #[derive(new, Message)]
@@ -118,73 +706,1084 @@ where
     state: S,
 }
 
+#[derive(new, Message, Event, Clone, Debug)]
+pub struct TransitionStarted<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub from: S,
+    pub to: S,
+}
+
+#[derive(new, Message, Event, Clone, Debug)]
+pub struct TransitionCovered<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub from: S,
+    pub to: S,
+}
+
+#[derive(new, Message, Event, Clone, Debug)]
+pub struct TransitionRevealStarted<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub from: S,
+    pub to: S,
+}
+
+#[derive(new, Message, Event, Clone, Debug)]
+pub struct TransitionFinished<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub from: S,
+    pub to: S,
+}
+
+/// Sent by [`Transition::cancel`], the reveal that follows finishes back at `from`.
+#[derive(new, Message, Event, Clone, Debug)]
+pub struct TransitionCancelled<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub from: S,
+    pub to: S,
+}
+
+/// Sent by [`Transition::redirect`] and when [`TransitionPolicy::Interrupt`] replaces the running
+/// transition, `to` is the new destination.
+#[derive(new, Message, Event, Clone, Debug)]
+pub struct TransitionRedirected<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub from: S,
+    pub to: S,
+    pub previous: S,
+}
+
+#[derive(new, Message, Event, Clone, Debug)]
+pub struct TransitionLoadFailed<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub state: S,
+    pub handle: UntypedHandle,
+    pub fallback: Option<S>,
+}
+
 #[derive(SystemParam)]
-pub struct Transition<'w, S>
+pub struct Transition<'w, 's, S>
 where
     S: FreelyMutableState + Reflectable,
 {
+    commands: Commands<'w, 's>,
+    state: Res<'w, State<S>>,
     writer: MessageWriter<'w, TransitionMessage<S>>,
-    speed: ResMut<'w, TransitionSpeed>,
+    settings: ResMut<'w, TransitionSettings<S>>,
+    status: ResMut<'w, TransitionStatus<S>>,
     pending_state: ResMut<'w, PendingState<S>>,
+    loading: ResMut<'w, LoadingSet<S>>,
+    routes: Res<'w, TransitionRoutes<S>>,
+    profile: Option<Res<'w, TransitionProfileHandle<S>>>,
+    profiles: Option<Res<'w, Assets<TransitionProfile>>>,
 }
 
-impl<S> Transition<'_, S>
+impl<S> Transition<'_, '_, S>
 where
     S: FreelyMutableState + Reflectable,
 {
+    /// Transitions to `state` styled by the matching [`TransitionProfile`] and
+    /// [`TransitionRoutes`] entries, if any.
+    ///
+    /// What happens when another transition is in flight is up to the [`TransitionPolicy`].
     pub fn to(&mut self, state: S) {
-        self.pending_state.0 = Some(state);
-        self.set_speed(self.speed().abs());
+        self.to_with(state, Default::default());
+    }
+
+    /// Transitions to `state` and keeps the screen covered until every handle has loaded.
+    pub fn to_loading(&mut self, state: S, handles: impl IntoIterator<Item = UntypedHandle>) {
+        self.request(QueuedTransition {
+            state,
+            options: Default::default(),
+            handles: handles.into_iter().collect(),
+        });
+    }
+
+    /// Transitions to `state` with options that only apply to this transition, anything left
+    /// unset falls back to the named [`TransitionOptions::style`], the route and then to the
+    /// [`TransitionSettings`].
+    pub fn to_with(&mut self, state: S, options: TransitionOptions) {
+        self.request(QueuedTransition {
+            state,
+            options,
+            handles: Vec::new(),
+        });
+    }
+
+    pub fn to_with_color(&mut self, state: S, color: impl Into<Color>) {
+        self.to_with(
+            state,
+            TransitionOptions {
+                color: Some(color.into()),
+                ..Default::default()
+            },
+        );
+    }
+
+    pub fn to_with_effect(&mut self, state: S, effect: impl Into<SharedEffect>) {
+        self.to_with(
+            state,
+            TransitionOptions {
+                effect: Some(effect.into()),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Reveals again from wherever the cover is without changing state, returns `false` when
+    /// there is nothing to cancel because the screen is already covered or no transition runs.
+    ///
+    /// Queued requests still start once the reveal finishes.
+    pub fn cancel(&mut self) -> bool {
+        if self.status.phase != TransitionPhase::Covering || !self.status.is_transitioning() {
+            return false;
+        }
+
+        self.emit(TransitionCancelled::new);
+
+        let from = self.state.get().clone();
+        self.pending_state.current = None;
+        self.loading.clear();
+        self.status.phase = TransitionPhase::Revealing;
+        self.status.route = Some((from.clone(), from));
+        true
+    }
+
+    /// Switches the destination of the running transition to `state`, returns `false` when no
+    /// transition runs.
+    ///
+    /// The screen stays covered until `state` has been entered, a transition that is already
+    /// revealing covers again first.
+    pub fn redirect(&mut self, state: S) -> bool {
+        let Some((from, previous)) = self.status.route.clone() else {
+            return false;
+        };
+
+        let from = match self.status.phase {
+            TransitionPhase::Covering => {
+                self.pending_state.current = Some(state.clone());
+                from
+            }
+            TransitionPhase::Covered => {
+                // the previous destination may already be entered, wait for the new one and hold
+                // and load for it as if it was covered just now
+                self.writer.write(TransitionMessage::new(state.clone()));
+                self.loading.clear();
+                self.status.held = Duration::ZERO;
+                self.status.state_applied = false;
+                self.status.ready = false;
+                from
+            }
+            TransitionPhase::Revealing | TransitionPhase::Idle => {
+                self.pending_state.current = Some(state.clone());
+                self.status.phase = TransitionPhase::Covering;
+                self.state.get().clone()
+            }
+        };
+
+        self.status.route = Some((from, state));
+        self.emit(|from, to| TransitionRedirected::new(from, to, previous));
+        true
+    }
+
+    /// The effect of the current transition, either the per call override or the default.
+    pub fn effect(&self) -> SharedEffect {
+        self.status
+            .options
+            .effect
+            .clone()
+            .unwrap_or(self.settings.effect.clone())
+    }
+
+    /// The overlay colour of the current transition, either the per call override or the default.
+    pub fn color(&self) -> Color {
+        self.status.options.color.unwrap_or(self.settings.color)
+    }
+
+    pub fn set_color(&mut self, color: impl Into<Color>) {
+        self.settings.color = color.into();
+    }
+
+    pub fn status(&self) -> &TransitionStatus<S> {
+        &self.status
+    }
+
+    pub fn settings(&self) -> &TransitionSettings<S> {
+        &self.settings
+    }
+
+    pub fn settings_mut(&mut self) -> &mut TransitionSettings<S> {
+        &mut self.settings
+    }
+
+    pub fn fade_out(&self) -> Duration {
+        self.settings.fade_out
+    }
+
+    pub fn set_fade_out(&mut self, fade_out: Duration) {
+        self.settings.fade_out = fade_out;
+    }
+
+    pub fn hold(&self) -> Duration {
+        self.settings.hold
+    }
+
+    pub fn set_hold(&mut self, hold: Duration) {
+        self.settings.hold = hold;
     }
 
+    pub fn fade_in(&self) -> Duration {
+        self.settings.fade_in
+    }
+
+    pub fn set_fade_in(&mut self, fade_in: Duration) {
+        self.settings.fade_in = fade_in;
+    }
+
+    /// Alpha per second of the current fade, positive while covering and negative while revealing.
     pub fn speed(&self) -> f32 {
-        self.speed.0
+        match self.status.phase {
+            TransitionPhase::Covering => duration_to_speed(self.current_fade_out()),
+            TransitionPhase::Covered => 0.0,
+            TransitionPhase::Revealing | TransitionPhase::Idle => {
+                -duration_to_speed(self.current_fade_in())
+            }
+        }
     }
 
+    /// Sets both fade durations from an alpha per second speed, the sign picks the direction.
     pub fn set_speed(&mut self, speed: f32) {
-        self.speed.0 = speed;
+        let duration = speed_to_duration(speed);
+        self.settings.fade_out = duration;
+        self.settings.fade_in = duration;
+
+        if speed > 0.0 {
+            self.status.phase = TransitionPhase::Covering;
+        } else if speed < 0.0 {
+            self.status.phase = TransitionPhase::Revealing;
+        }
     }
 
-    fn take_pending(&mut self) -> Option<S> {
-        self.pending_state.0.take()
+    fn current_fade_out(&self) -> Duration {
+        self.status
+            .options
+            .fade_out
+            .unwrap_or(self.settings.fade_out)
     }
-}
 
-#[derive(Resource, Reflect)]
-#[reflect(Resource)]
-pub struct PendingState<S>(Option<S>)
-where
-    S: FreelyMutableState + Reflectable;
+    fn current_hold(&self) -> Duration {
+        self.status.options.hold.unwrap_or(self.settings.hold)
+    }
 
-impl<S> Default for PendingState<S>
-where
-    S: FreelyMutableState + Reflectable,
-{
-    fn default() -> Self {
-        Self(None)
+    fn current_fade_in(&self) -> Duration {
+        self.status.options.fade_in.unwrap_or(self.settings.fade_in)
     }
-}
 
-#[derive(Resource, Reflect)]
-#[reflect(Resource, Default)]
-pub struct TransitionSpeed(f32);
+    fn current_fade_out_easing(&self) -> &TransitionCurve {
+        let easing = self.status.options.easing.as_ref();
+        easing.unwrap_or(&self.settings.fade_out_easing)
+    }
 
-impl Default for TransitionSpeed {
-    fn default() -> Self {
-        Self(2.0)
+    fn current_fade_in_easing(&self) -> &TransitionCurve {
+        let easing = self.status.options.easing.as_ref();
+        easing.unwrap_or(&self.settings.fade_in_easing)
     }
-}
-#[derive(Component)]
-#[relationship_target(relationship=OverlayOf, linked_spawn)]
-struct Overlays(Vec<Entity>);
 
-#[derive(Component)]
-#[relationship(relationship_target=Overlays)]
-struct OverlayOf(Entity);
+    fn current_clock(&self) -> TransitionClock {
+        self.status.options.clock.unwrap_or(self.settings.clock)
+    }
+
+    fn profile(&self) -> Option<&TransitionProfile> {
+        self.profiles.as_ref()?.get(&self.profile.as_ref()?.handle)
+    }
+
+    fn request(&mut self, request: QueuedTransition<S>) {
+        if !self.status.is_transitioning() {
+            self.start(request);
+            return;
+        }
+
+        let queue = &mut self.pending_state.queue;
+        match self.settings.policy {
+            TransitionPolicy::Interrupt => self.start(request),
+            TransitionPolicy::Queue => queue.push_back(request),
+            TransitionPolicy::ReplaceLatest => {
+                queue.clear();
+                queue.push_back(request);
+            }
+            TransitionPolicy::IgnoreWhileBusy => {}
+        }
+    }
+
+    fn start(&mut self, request: QueuedTransition<S>) {
+        let QueuedTransition {
+            state,
+            options,
+            handles,
+        } = request;
+
+        let from = self.state.get().clone();
+        let previous = self.status.route.as_ref().map(|(_, to)| to.clone());
+        let profile = self.profile();
+        let style = options
+            .style
+            .as_deref()
+            .and_then(|style| profile?.style(style))
+            .cloned()
+            .unwrap_or_default();
+        let route = self.route(&from, &state).cloned().unwrap_or_default();
+
+        self.status.options = options.or(style).or(route);
+        self.status.phase = TransitionPhase::Covering;
+        self.status.route = Some((from, state.clone()));
+        self.status.warned_headless = false;
+        self.pending_state.current = Some(state);
+
+        // the abandoned destination's assets no longer gate the reveal
+        if previous.is_some() {
+            self.loading.clear();
+        }
+        self.loading.extend(handles);
+
+        // an interrupted transition carries on towards the new state, so it still ends once
+        match previous {
+            Some(previous) => self.emit(|from, to| TransitionRedirected::new(from, to, previous)),
+            None => self.emit(TransitionStarted::new),
+        }
+    }
+
+    // the most specific route wins, the profile's over the code's at the same specificity
+    fn route(&self, from: &S, to: &S) -> Option<&TransitionOptions> {
+        let profile = self.profile();
+        let profile_keys = route_keys(format!("{from:?}"), format!("{to:?}"));
+        let keys = route_keys(from.clone(), to.clone());
+
+        profile_keys
+            .iter()
+            .zip(&keys)
+            .find_map(|(profile_key, key)| {
+                profile
+                    .and_then(|profile| profile.route_by_key(profile_key))
+                    .or_else(|| self.routes.routes.get(key))
+            })
+            .or_else(|| profile?.default_style())
+            .or(self.routes.default.as_ref())
+    }
+
+    fn start_next(&mut self) {
+        if let Some(request) = self.pending_state.queue.pop_front() {
+            self.start(request);
+        }
+    }
+
+    fn take_pending(&mut self) -> Option<S> {
+        self.pending_state.current.take()
+    }
+
+    fn start_reveal(&mut self) {
+        self.loading.clear();
+        self.status.phase = TransitionPhase::Revealing;
+        self.emit(TransitionRevealStarted::new);
+    }
+
+    // lifecycle events go out both as messages and as observer triggers,
+    // and only while a transition is in flight
+    fn emit<E>(&mut self, event: impl FnOnce(S, S) -> E)
+    where
+        E: Message + for<'a> Event<Trigger<'a>: Default> + Clone,
+    {
+        let Some((from, to)) = self.status.route.clone() else {
+            return;
+        };
+
+        let event = event(from, to);
+        self.commands.write_message(event.clone());
+        self.commands.trigger(event);
+    }
+}
+
+/// The state the running transition switches to once covered, and the requests waiting for it
+/// to finish under [`TransitionPolicy::Queue`] or [`TransitionPolicy::ReplaceLatest`].
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct PendingState<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    current: Option<S>,
+    queue: VecDeque<QueuedTransition<S>>,
+}
+
+impl<S> PendingState<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    /// The destination of the running transition until the screen is covered.
+    pub fn current(&self) -> Option<&S> {
+        self.current.as_ref()
+    }
+
+    /// The requests that start one after the other once the running transition finishes.
+    pub fn queued(&self) -> impl Iterator<Item = &QueuedTransition<S>> {
+        self.queue.iter()
+    }
+
+    pub fn clear_queue(&mut self) {
+        self.queue.clear();
+    }
+}
+
+impl<S> Default for PendingState<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    fn default() -> Self {
+        Self {
+            current: None,
+            queue: Default::default(),
+        }
+    }
+}
+
+#[derive(Reflect, Clone)]
+pub struct QueuedTransition<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub state: S,
+    #[reflect(ignore)]
+    pub options: TransitionOptions,
+    #[reflect(ignore)]
+    pub handles: Vec<UntypedHandle>,
+}
+
+/// How a transition runs while no camera has an overlay, such as in headless tests or after the
+/// camera was despawned mid fade. A warning is logged either way.
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum OverlayFallback {
+    /// Skips the fades and the hold, the state still waits for readiness.
+    Immediate,
+    /// Keeps the configured timing as if the overlay was there.
+    #[default]
+    AfterDuration,
+}
+
+/// What [`Transition::to`] does while another transition is in flight.
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum TransitionPolicy {
+    /// Plays every request in order once the running transition finishes.
+    Queue,
+    /// Plays only the most recent request once the running transition finishes.
+    ReplaceLatest,
+    /// Drops requests until the running transition finishes.
+    IgnoreWhileBusy,
+    /// Takes over right away, a reveal turns around and covers again from where it is.
+    #[default]
+    Interrupt,
+}
+
+#[derive(Resource, Clone)]
+pub struct TransitionSettings<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub fade_out: Duration,
+    pub hold: Duration,
+    pub fade_in: Duration,
+    pub fade_out_easing: TransitionCurve,
+    pub fade_in_easing: TransitionCurve,
+    pub color: Color,
+    pub effect: SharedEffect,
+    pub z_index: i32,
+    pub error_state: Option<S>,
+    pub loading_screen: Option<LoadingScreen>,
+    pub policy: TransitionPolicy,
+    pub overlay_fallback: OverlayFallback,
+    pub startup: StartupPolicy,
+    pub clock: TransitionClock,
+}
+
+impl<S> Default for TransitionSettings<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    fn default() -> Self {
+        Self {
+            fade_out: Duration::from_millis(500),
+            hold: Duration::ZERO,
+            fade_in: Duration::from_millis(500),
+            fade_out_easing: Default::default(),
+            fade_in_easing: Default::default(),
+            color: Color::BLACK,
+            effect: Default::default(),
+            z_index: i32::MAX,
+            error_state: None,
+            loading_screen: None,
+            policy: Default::default(),
+            overlay_fallback: Default::default(),
+            startup: Default::default(),
+            clock: Default::default(),
+        }
+    }
+}
+
+/// Overrides for a single transition, see [`Transition::to_with`].
+#[derive(Clone, Default)]
+pub struct TransitionOptions {
+    pub effect: Option<SharedEffect>,
+    pub color: Option<Color>,
+    pub fade_out: Option<Duration>,
+    pub hold: Option<Duration>,
+    pub fade_in: Option<Duration>,
+    /// Replaces both the fade out and the fade in easing.
+    pub easing: Option<TransitionCurve>,
+    pub clock: Option<TransitionClock>,
+    /// A style from the plugin's [`TransitionProfile`] filling the options left unset.
+    pub style: Option<String>,
+}
+
+impl TransitionOptions {
+    /// Options taken from a style of the plugin's [`TransitionProfile`].
+    pub fn styled(style: impl Into<String>) -> Self {
+        Self {
+            style: Some(style.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Fills every unset option from `other`.
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            effect: self.effect.or(other.effect),
+            color: self.color.or(other.color),
+            fade_out: self.fade_out.or(other.fade_out),
+            hold: self.hold.or(other.hold),
+            fade_in: self.fade_in.or(other.fade_in),
+            easing: self.easing.or(other.easing),
+            clock: self.clock.or(other.clock),
+            style: self.style.or(other.style),
+        }
+    }
+
+    /// Sets both fades to `duration` unless they are set individually.
+    pub fn or_duration(self, duration: Duration) -> Self {
+        Self {
+            fade_out: self.fade_out.or(Some(duration)),
+            fade_in: self.fade_in.or(Some(duration)),
+            ..self
+        }
+    }
+}
+
+/// Options per edge of the state graph, consulted by [`Transition::to`].
+///
+/// `None` for either side of a route matches any state. The most specific route wins: an exact
+/// `(from, to)` pair, then `(any, to)`, then `(from, any)`, then `(any, any)`, then the default.
+#[derive(Resource, Clone)]
+pub struct TransitionRoutes<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    routes: HashMap<(Option<S>, Option<S>), TransitionOptions>,
+    default: Option<TransitionOptions>,
+}
+
+impl<S> TransitionRoutes<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub fn with_route(
+        mut self,
+        from: impl Into<Option<S>>,
+        to: impl Into<Option<S>>,
+        options: TransitionOptions,
+    ) -> Self {
+        self.insert(from, to, options);
+        self
+    }
+
+    pub fn with_default(mut self, options: TransitionOptions) -> Self {
+        self.default = Some(options);
+        self
+    }
+
+    pub fn insert(
+        &mut self,
+        from: impl Into<Option<S>>,
+        to: impl Into<Option<S>>,
+        options: TransitionOptions,
+    ) {
+        self.routes.insert((from.into(), to.into()), options);
+    }
+
+    pub fn remove(&mut self, from: impl Into<Option<S>>, to: impl Into<Option<S>>) {
+        self.routes.remove(&(from.into(), to.into()));
+    }
+
+    pub fn set_default(&mut self, options: Option<TransitionOptions>) {
+        self.default = options;
+    }
+
+    pub fn get(&self, from: &S, to: &S) -> Option<&TransitionOptions> {
+        route_keys(from.clone(), to.clone())
+            .iter()
+            .find_map(|key| self.routes.get(key))
+            .or(self.default.as_ref())
+    }
+}
+
+impl<S> Default for TransitionRoutes<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    fn default() -> Self {
+        Self {
+            routes: Default::default(),
+            default: None,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct TransitionStatus<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    progress: f32,
+    phase: TransitionPhase,
+    held: Duration,
+    state_applied: bool,
+    ready: bool,
+    loading_progress: f32,
+    alpha: f32,
+    options: TransitionOptions,
+    route: Option<(S, S)>,
+    warned_headless: bool,
+}
+
+impl<S> TransitionStatus<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub fn phase(&self) -> TransitionPhase {
+        self.phase
+    }
+
+    /// How far the screen is covered, from 0 (clear) to 1 (fully covered), before easing.
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    /// The eased coverage the effect renders, the overlay alpha for a fade.
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    /// How much of the readiness gate is satisfied while covered, from 0 to 1.
+    pub fn loading_progress(&self) -> f32 {
+        self.loading_progress
+    }
+
+    /// The per call overrides of the current transition.
+    pub fn options(&self) -> &TransitionOptions {
+        &self.options
+    }
+
+    pub fn from(&self) -> Option<&S> {
+        self.route.as_ref().map(|(from, _)| from)
+    }
+
+    pub fn to(&self) -> Option<&S> {
+        self.route.as_ref().map(|(_, to)| to)
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.phase == TransitionPhase::Idle
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        self.route.is_some()
+    }
+
+    /// Whether every readiness condition and [`TransitionHold`] allows the screen to reveal.
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+}
+
+impl<S> Default for TransitionStatus<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    fn default() -> Self {
+        Self::starting(Default::default())
+    }
+}
+
+impl<S> TransitionStatus<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    fn starting(startup: StartupPolicy) -> Self {
+        let (phase, progress, fade_in) = match startup {
+            StartupPolicy::Reveal(fade_in) => (TransitionPhase::Revealing, 1.0, Some(fade_in)),
+            StartupPolicy::Clear => (TransitionPhase::Idle, 0.0, None),
+            StartupPolicy::CoveredUntilReady => (TransitionPhase::Covered, 1.0, None),
+        };
+
+        Self {
+            progress,
+            phase,
+            held: Duration::ZERO,
+            // the initial state is entered before the first frame
+            state_applied: true,
+            ready: false,
+            loading_progress: 0.0,
+            alpha: progress,
+            options: TransitionOptions {
+                fade_in,
+                ..Default::default()
+            },
+            route: None,
+            warned_headless: false,
+        }
+    }
+}
+
+/// The time that advances the fades and the hold.
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum TransitionClock {
+    /// The `Time` of the schedule the plugin runs in, virtual time in `Update`.
+    #[default]
+    Time,
+    /// Stops while `Time<Virtual>` is paused.
+    Virtual,
+    /// Keeps running while the game is paused, such as for transitions into a pause menu.
+    Real,
+    /// Advances by a fixed step every frame, so a fade lasts a set number of frames.
+    Frames(Duration),
+}
+
+/// What the overlay shows on the first frame, before any [`Transition::to`].
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum StartupPolicy {
+    /// Starts covered and reveals over the given duration right away.
+    Reveal(Duration),
+    /// Starts transparent.
+    Clear,
+    /// Starts covered and reveals over the fade in once every readiness condition,
+    /// [`TransitionHold`] and the [`LoadingSet`] allow it, as after any other transition.
+    #[default]
+    CoveredUntilReady,
+}
+
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TransitionPhase {
+    Idle,
+    Covering,
+    Covered,
+    Revealing,
+}
+
+/// Spawns the content shown on top of the overlay while it is held covered.
+#[derive(Clone)]
+pub struct LoadingScreen(Arc<dyn Fn(&mut ChildSpawnerCommands) + Send + Sync>);
+
+impl LoadingScreen {
+    pub fn new(spawn: impl Fn(&mut ChildSpawnerCommands) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(spawn))
+    }
+}
+
+/// Loading screen content whose width follows [`TransitionStatus::loading_progress`].
+#[derive(Component, Default)]
+pub struct LoadingProgressBar;
 
 #[derive(Component)]
-struct FadeOverlay;
+struct LoadingScreenRoot<S>(PhantomData<S>)
+where
+    S: FreelyMutableState + Reflectable;
 
+impl<S> Default for LoadingScreenRoot<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+#[derive(QueryData)]
+#[query_data(mutable)]
+struct LoadingScreenContent {
+    base: Option<&'static LoadingScreenAlpha>,
+    background: Option<&'static mut BackgroundColor>,
+    text: Option<&'static mut TextColor>,
+    image: Option<&'static mut ImageNode>,
+    node: Option<&'static mut Node>,
+    progress_bar: Has<LoadingProgressBar>,
+}
+
+#[derive(Component)]
+struct LoadingScreenAlpha {
+    background: Option<f32>,
+    text: Option<f32>,
+    image: Option<f32>,
+}
+
+#[derive(Clone)]
+pub struct TransitionCurve(Arc<dyn Curve<f32> + Send + Sync>);
+
+impl TransitionCurve {
+    pub fn new(curve: impl Curve<f32> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(curve))
+    }
+
+    pub fn sample(&self, t: f32) -> f32 {
+        // t is normalized, remap it onto the curve's domain when that domain is bounded
+        let domain = self.0.domain();
+        let t = if domain.is_bounded() {
+            domain.start() + t * domain.length()
+        } else {
+            t
+        };
+        self.0.sample_clamped(t)
+    }
+}
+
+impl Default for TransitionCurve {
+    fn default() -> Self {
+        Self::from(EaseFunction::Linear)
+    }
+}
+
+impl From<EaseFunction> for TransitionCurve {
+    fn from(ease: EaseFunction) -> Self {
+        Self::new(ease)
+    }
+}
+
+/// Assets the covered screen waits on before revealing, fill it from `OnEnter` or through
+/// [`Transition::to_loading`]. Folders can be added with the untyped `load_folder` handle.
+/// The set is cleared once the reveal starts, so keep your own handles to the assets.
+#[derive(Resource)]
+pub struct LoadingSet<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    handles: Vec<UntypedHandle>,
+    _marker: PhantomData<S>,
+}
+
+impl<S> LoadingSet<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub fn add(&mut self, handle: impl Into<UntypedHandle>) {
+        self.handles.push(handle.into());
+    }
+
+    pub fn handles(&self) -> &[UntypedHandle] {
+        &self.handles
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.handles.clear();
+    }
+}
+
+impl<S> Extend<UntypedHandle> for LoadingSet<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    fn extend<T: IntoIterator<Item = UntypedHandle>>(&mut self, iter: T) {
+        self.handles.extend(iter);
+    }
+}
+
+impl<S> Default for LoadingSet<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    fn default() -> Self {
+        Self {
+            handles: Default::default(),
+            _marker: Default::default(),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct TransitionConditions<S>(Vec<SystemId<(), bool>>, PhantomData<S>)
+where
+    S: FreelyMutableState + Reflectable;
+
+impl<S> Default for TransitionConditions<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    fn default() -> Self {
+        Self(Default::default(), Default::default())
+    }
+}
+
+/// Keeps the screen covered while it exists, spawn one from `OnEnter` and despawn it once loaded.
+#[derive(Component)]
+pub struct TransitionHold<S>(PhantomData<S>)
+where
+    S: FreelyMutableState + Reflectable;
+
+impl<S> Default for TransitionHold<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+/// States with their transition routes declared on the type, usually through
+/// `#[derive(TransitionStates)]`.
+///
+/// The derive rejects a second default route:
+///
+/// ```compile_fail
+/// # use bevy::prelude::*;
+/// # use bevy_transitions::TransitionStates;
+/// #[derive(States, TransitionStates, Reflect, Clone, PartialEq, Eq, Hash, Debug, Default)]
+/// #[transition(duration_ms = 400)]
+/// #[transition(duration_ms = 200)]
+/// enum GameState {
+///     #[default]
+///     Menu,
+/// }
+/// ```
+///
+/// and colours that are neither a css name nor a hex string:
+///
+/// ```compile_fail
+/// # use bevy::prelude::*;
+/// # use bevy_transitions::TransitionStates;
+/// #[derive(States, TransitionStates, Reflect, Clone, PartialEq, Eq, Hash, Debug, Default)]
+/// enum GameState {
+///     #[default]
+///     Menu,
+///     #[transition(color = "dark red")]
+///     Playing,
+/// }
+/// ```
+pub trait TransitionStates: FreelyMutableState + Reflectable {
+    fn transition_routes() -> TransitionRoutes<Self>;
+
+    /// A plugin for these states that uses the declared routes.
+    fn transitions_plugin<C>() -> TransitionsPlugin<Self, C>
+    where
+        C: Component,
+    {
+        TransitionsPlugin::default().with_routes(Self::transition_routes())
+    }
+}
+
+pub trait AppExtTransitions {
+    /// Registers a system that must return `true` before the covered screen of `S` is revealed.
+    fn add_transition_condition<S, M>(
+        &mut self,
+        condition: impl IntoSystem<(), bool, M> + 'static,
+    ) -> &mut Self
+    where
+        S: FreelyMutableState + Reflectable;
+
+    /// Registers an effect under a name so it can be referred to from data.
+    fn register_transition_effect(
+        &mut self,
+        name: impl Into<String>,
+        effect: impl Into<SharedEffect>,
+    ) -> &mut Self;
+}
+
+impl AppExtTransitions for App {
+    fn add_transition_condition<S, M>(
+        &mut self,
+        condition: impl IntoSystem<(), bool, M> + 'static,
+    ) -> &mut Self
+    where
+        S: FreelyMutableState + Reflectable,
+    {
+        let condition = self.world_mut().register_system(condition);
+        self.world_mut()
+            .get_resource_or_init::<TransitionConditions<S>>()
+            .0
+            .push(condition);
+        self
+    }
+
+    fn register_transition_effect(
+        &mut self,
+        name: impl Into<String>,
+        effect: impl Into<SharedEffect>,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<TransitionEffects>()
+            .insert(name, effect);
+        self
+    }
+}
+
+/// Effect names that failed to resolve and were already reported.
+#[derive(Resource, Default)]
+struct UnknownEffects(HashSet<String>);
+
+#[derive(Component)]
+#[relationship_target(relationship=OverlayOf, linked_spawn)]
+struct Overlays(Vec<Entity>);
+
+#[derive(Component)]
+#[relationship(relationship_target=Overlays)]
+struct OverlayOf(Entity);
+
+#[derive(Component)]
+struct FadeOverlay<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    camera: Entity,
+    content: Option<(Entity, Arc<dyn TransitionEffect>)>,
+    _marker: PhantomData<S>,
+}
+
+impl<S> FadeOverlay<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    fn new(camera: Entity) -> Self {
+        Self {
+            camera,
+            content: None,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Consumes every [`TransitionMessage`], so any other reader of them in the same frame misses
+/// the state change.
+#[deprecated(note = "consumes the transition messages, use `in_transition` or `transitioning_to`")]
 pub fn is_transition_pending<S>(mut events: MessageReader<TransitionMessage<S>>) -> bool
 where
     S: FreelyMutableState + Clone,
@@ -193,3 +1792,81 @@ where
     events.clear();
     had_events
 }
+
+// the most specific first: the exact pair, then any state into `to`, then `from` into any state,
+// then any state into any state
+fn route_keys<K>(from: K, to: K) -> [(Option<K>, Option<K>); 4]
+where
+    K: Clone,
+{
+    [
+        (Some(from.clone()), Some(to.clone())),
+        (None, Some(to)),
+        (Some(from), None),
+        (None, None),
+    ]
+}
+
+// shown once covered and faded out together with the overlay while revealing
+fn loading_screen_display(phase: TransitionPhase) -> Display {
+    match phase {
+        TransitionPhase::Covered | TransitionPhase::Revealing => Display::Flex,
+        TransitionPhase::Idle | TransitionPhase::Covering => Display::None,
+    }
+}
+
+fn advance(progress: f32, delta: Duration, duration: Duration) -> f32 {
+    if duration.is_zero() {
+        return 1.0;
+    }
+
+    (progress + delta.as_secs_f32() / duration.as_secs_f32()).clamp(0.0, 1.0)
+}
+
+fn duration_to_speed(duration: Duration) -> f32 {
+    if duration.is_zero() {
+        f32::INFINITY
+    } else {
+        1.0 / duration.as_secs_f32()
+    }
+}
+
+fn speed_to_duration(speed: f32) -> Duration {
+    Duration::try_from_secs_f32(1.0 / speed.abs()).unwrap_or(Duration::MAX)
+}
+
+pub fn in_transition<S>() -> impl FnMut(Option<Res<TransitionStatus<S>>>) -> bool + Clone
+where
+    S: FreelyMutableState + Reflectable,
+{
+    |status: Option<Res<TransitionStatus<S>>>| {
+        status.is_some_and(|status| status.is_transitioning())
+    }
+}
+
+pub fn transition_covering<S>() -> impl FnMut(Option<Res<TransitionStatus<S>>>) -> bool + Clone
+where
+    S: FreelyMutableState + Reflectable,
+{
+    |status: Option<Res<TransitionStatus<S>>>| {
+        status.is_some_and(|status| status.phase() == TransitionPhase::Covering)
+    }
+}
+
+pub fn transition_revealing<S>() -> impl FnMut(Option<Res<TransitionStatus<S>>>) -> bool + Clone
+where
+    S: FreelyMutableState + Reflectable,
+{
+    |status: Option<Res<TransitionStatus<S>>>| {
+        status.is_some_and(|status| status.phase() == TransitionPhase::Revealing)
+    }
+}
+
+pub fn transitioning_to<S>(state: S) -> impl FnMut(Option<Res<TransitionStatus<S>>>) -> bool + Clone
+where
+    S: FreelyMutableState + Reflectable,
+{
+    move |status: Option<Res<TransitionStatus<S>>>| {
+        status.is_some_and(|status| status.to() == Some(&state))
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{SharedEffect, TransitionCurve, TransitionOptions, route_keys};
+use bevy_asset::{AssetLoader, LoadContext, io::Reader, prelude::*};
+use bevy_color::{HexColorError, prelude::*};
+use bevy_ecs::prelude::*;
+use bevy_math::curve::EaseFunction;
+use bevy_reflect::Reflectable;
+use bevy_reflect::prelude::*;
+use bevy_state::state::FreelyMutableState;
+use ron::extensions::Extensions;
+use serde::Deserialize;
+use std::{collections::HashMap, fmt, marker::PhantomData, time::Duration};
+
+/// Named transition styles and the routes that use them, loaded from a `.transitions.ron` file.
+///
+/// Routes refer to states by their `Debug` name, so `Menu` or `Level(2)`, and to styles by name.
+/// Leaving out `from` or `to` matches any state on that side, as with
+/// [`TransitionRoutes`](crate::TransitionRoutes). Colours are hex strings only, the css names
+/// that `#[derive(TransitionStates)]` accepts, such as `"black"`, are rejected.
+/// Styles can also be picked per call through [`TransitionOptions::style`]. Edits to the file
+/// apply from the next transition when bevy_asset's `file_watcher` feature is enabled.
+///
+/// ```ron
+/// (
+///     styles: {
+///         "slow": (effect: "fade", fade_out_ms: 1000, fade_in_ms: 1000),
+///         "flash": (color: "#ffffff", fade_out_ms: 50, hold_ms: 100, easing: QuadraticOut),
+///     },
+///     routes: [
+///         (from: "Menu", to: "Playing", style: "slow"),
+///         (to: "GameOver", style: "flash"),
+///     ],
+///     default: "slow",
+/// )
+/// ```
+#[derive(Asset, TypePath, Clone, Default)]
+pub struct TransitionProfile {
+    styles: HashMap<String, TransitionOptions>,
+    routes: HashMap<(Option<String>, Option<String>), String>,
+    default: Option<String>,
+}
+
+impl TransitionProfile {
+    pub fn style(&self, name: &str) -> Option<&TransitionOptions> {
+        self.styles.get(name)
+    }
+
+    /// The style of the most specific route from `from` to `to`, with the same precedence as
+    /// [`TransitionRoutes`](crate::TransitionRoutes), without falling back to the default.
+    pub fn route<S>(&self, from: &S, to: &S) -> Option<&TransitionOptions>
+    where
+        S: fmt::Debug,
+    {
+        route_keys(format!("{from:?}"), format!("{to:?}"))
+            .iter()
+            .find_map(|key| self.route_by_key(key))
+    }
+
+    /// The style used when no route matches.
+    pub fn default_style(&self) -> Option<&TransitionOptions> {
+        self.style(self.default.as_deref()?)
+    }
+
+    pub(crate) fn route_by_key(
+        &self,
+        key: &(Option<String>, Option<String>),
+    ) -> Option<&TransitionOptions> {
+        self.style(self.routes.get(key)?)
+    }
+
+    pub(crate) fn parse(bytes: &[u8]) -> Result<Self, TransitionProfileError> {
+        // lets optional fields be written without `Some(...)`
+        ron::Options::default()
+            .with_default_extension(Extensions::IMPLICIT_SOME)
+            .from_bytes::<ProfileDef>(bytes)?
+            .try_into()
+    }
+}
+
+/// The profile a plugin for `S` reads its styles and routes from, see
+/// [`TransitionsPlugin::with_profile`](crate::TransitionsPlugin::with_profile).
+#[derive(Resource, Clone)]
+pub struct TransitionProfileHandle<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub handle: Handle<TransitionProfile>,
+    _marker: PhantomData<S>,
+}
+
+impl<S> TransitionProfileHandle<S>
+where
+    S: FreelyMutableState + Reflectable,
+{
+    pub fn new(handle: Handle<TransitionProfile>) -> Self {
+        Self {
+            handle,
+            _marker: Default::default(),
+        }
+    }
+}
+
+#[derive(Default, TypePath)]
+pub struct TransitionProfileLoader;
+
+impl AssetLoader for TransitionProfileLoader {
+    type Asset = TransitionProfile;
+    type Settings = ();
+    type Error = TransitionProfileError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        TransitionProfile::parse(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["transitions.ron"]
+    }
+}
+
+#[derive(Debug)]
+pub enum TransitionProfileError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    Color(String, HexColorError),
+    UnknownStyle(String),
+}
+
+impl fmt::Display for TransitionProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read transition profile: {error}"),
+            Self::Ron(error) => write!(f, "could not parse transition profile: {error}"),
+            Self::Color(color, error) => write!(f, "invalid hex colour `{color}`: {error}"),
+            Self::UnknownStyle(style) => write!(f, "route refers to unknown style `{style}`"),
+        }
+    }
+}
+
+impl std::error::Error for TransitionProfileError {}
+
+impl From<std::io::Error> for TransitionProfileError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for TransitionProfileError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        Self::Ron(error)
+    }
+}
+
+#[derive(Deserialize)]
+struct ProfileDef {
+    #[serde(default)]
+    styles: HashMap<String, StyleDef>,
+    #[serde(default)]
+    routes: Vec<RouteDef>,
+    #[serde(default)]
+    default: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct StyleDef {
+    effect: Option<String>,
+    color: Option<String>,
+    easing: Option<EaseFunction>,
+    duration_ms: Option<u64>,
+    fade_out_ms: Option<u64>,
+    hold_ms: Option<u64>,
+    fade_in_ms: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RouteDef {
+    #[serde(default)]
+    from: Option<String>,
+    #[serde(default)]
+    to: Option<String>,
+    style: String,
+}
+
+impl TryFrom<ProfileDef> for TransitionProfile {
+    type Error = TransitionProfileError;
+
+    fn try_from(def: ProfileDef) -> Result<Self, Self::Error> {
+        let styles = def
+            .styles
+            .into_iter()
+            .map(|(name, style)| Ok((name, style.try_into()?)))
+            .collect::<Result<HashMap<_, _>, TransitionProfileError>>()?;
+
+        let known = |style: String| {
+            if styles.contains_key(&style) {
+                Ok(style)
+            } else {
+                Err(TransitionProfileError::UnknownStyle(style))
+            }
+        };
+
+        let routes = def
+            .routes
+            .into_iter()
+            .map(|route| Ok(((route.from, route.to), known(route.style)?)))
+            .collect::<Result<_, TransitionProfileError>>()?;
+        let default = def.default.map(known).transpose()?;
+
+        Ok(Self {
+            styles,
+            routes,
+            default,
+        })
+    }
+}
+
+impl TryFrom<StyleDef> for TransitionOptions {
+    type Error = TransitionProfileError;
+
+    fn try_from(def: StyleDef) -> Result<Self, Self::Error> {
+        let color = def
+            .color
+            .map(|color| match Srgba::hex(&color) {
+                Ok(srgba) => Ok(Color::from(srgba)),
+                Err(error) => Err(TransitionProfileError::Color(color, error)),
+            })
+            .transpose()?;
+
+        let millis = |ms: Option<u64>| ms.map(Duration::from_millis);
+        let options = Self {
+            effect: def.effect.map(SharedEffect::named),
+            color,
+            fade_out: millis(def.fade_out_ms),
+            hold: millis(def.hold_ms),
+            fade_in: millis(def.fade_in_ms),
+            easing: def.easing.map(TransitionCurve::from),
+            clock: None,
+            style: None,
+        };
+
+        Ok(match millis(def.duration_ms) {
+            Some(duration) => options.or_duration(duration),
+            None => options,
+        })
+    }
+}
@@ -0,0 +1,1004 @@
+use super::*;
+use bevy_app::TaskPoolPlugin;
+use bevy_asset::AssetPlugin;
+use bevy_ecs::system::SystemState;
+use bevy_state::app::StatesPlugin;
+use bevy_time::{TimePlugin, TimeUpdateStrategy};
+
+const STEP: Duration = Duration::from_millis(100);
+
+#[derive(States, Reflect, Clone, PartialEq, Eq, Hash, Debug, Default)]
+enum TestState {
+    #[default]
+    A,
+    B,
+    C,
+}
+
+#[derive(Component)]
+struct TestCamera;
+
+/// Lifecycle events and entered states in the order they happened.
+#[derive(Resource, Default)]
+struct Log(Vec<String>);
+
+fn log(app: &App) -> Vec<String> {
+    app.world().resource::<Log>().0.clone()
+}
+
+fn clear_log(app: &mut App) {
+    app.world_mut().resource_mut::<Log>().0.clear();
+}
+
+/// Clears on startup and fades over two updates.
+fn plugin() -> TransitionsPlugin<TestState, TestCamera> {
+    TransitionsPlugin::default()
+        .with_startup(StartupPolicy::Clear)
+        .with_durations(STEP * 2, STEP * 2)
+}
+
+/// A headless app advancing 100 ms per update.
+fn app(plugin: TransitionsPlugin<TestState, TestCamera>) -> App {
+    let mut app = app_with(App::new(), plugin);
+    app.update();
+    clear_log(&mut app);
+    app
+}
+
+fn app_with(mut app: App, plugin: TransitionsPlugin<TestState, TestCamera>) -> App {
+    app.add_plugins((TimePlugin, StatesPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(STEP))
+        .init_state::<TestState>()
+        .add_plugins(plugin)
+        .init_resource::<Log>()
+        .add_observer(
+            |event: On<TransitionStarted<TestState>>, mut log: ResMut<Log>| {
+                log.0
+                    .push(format!("started {:?} {:?}", event.from, event.to));
+            },
+        )
+        .add_observer(
+            |event: On<TransitionRedirected<TestState>>, mut log: ResMut<Log>| {
+                log.0
+                    .push(format!("redirected {:?} {:?}", event.previous, event.to));
+            },
+        )
+        .add_observer(
+            |event: On<TransitionCancelled<TestState>>, mut log: ResMut<Log>| {
+                log.0
+                    .push(format!("cancelled {:?} {:?}", event.from, event.to));
+            },
+        )
+        .add_observer(
+            |event: On<TransitionFinished<TestState>>, mut log: ResMut<Log>| {
+                log.0
+                    .push(format!("finished {:?} {:?}", event.from, event.to));
+            },
+        )
+        .add_observer(
+            |_: On<TransitionRevealStarted<TestState>>, mut log: ResMut<Log>| {
+                log.0.push("revealing".into());
+            },
+        )
+        .add_observer(
+            |event: On<TransitionLoadFailed<TestState>>, mut log: ResMut<Log>| {
+                log.0
+                    .push(format!("failed {:?} {:?}", event.state, event.fallback));
+            },
+        )
+        .add_systems(OnEnter(TestState::A), |mut log: ResMut<Log>| {
+            log.0.push("entered A".into())
+        })
+        .add_systems(OnEnter(TestState::B), |mut log: ResMut<Log>| {
+            log.0.push("entered B".into())
+        })
+        .add_systems(OnEnter(TestState::C), |mut log: ResMut<Log>| {
+            log.0.push("entered C".into())
+        });
+    app
+}
+
+fn default_app() -> App {
+    app(plugin())
+}
+
+fn transition<O>(app: &mut App, f: impl FnOnce(&mut Transition<TestState>) -> O) -> O {
+    let mut system_state = SystemState::<Transition<TestState>>::new(app.world_mut());
+    let output = f(&mut system_state.get_mut(app.world_mut()));
+    system_state.apply(app.world_mut());
+    output
+}
+
+fn state(app: &App) -> TestState {
+    app.world().resource::<State<TestState>>().get().clone()
+}
+
+fn phase(app: &App) -> TransitionPhase {
+    app.world()
+        .resource::<TransitionStatus<TestState>>()
+        .phase()
+}
+
+/// Returns the number of updates it took.
+fn update_until_idle(app: &mut App) -> usize {
+    for updates in 1..=50 {
+        app.update();
+        if phase(app) == TransitionPhase::Idle {
+            return updates;
+        }
+    }
+
+    panic!("transition did not finish, stuck in {:?}", phase(app));
+}
+
+#[test]
+fn state_changes_in_the_covering_frame() {
+    let mut app = default_app();
+    transition(&mut app, |transition| transition.to(TestState::B));
+
+    app.update();
+    assert_eq!(phase(&app), TransitionPhase::Covering);
+    assert_eq!(state(&app), TestState::A);
+
+    app.update();
+    assert_eq!(phase(&app), TransitionPhase::Covered);
+    assert_eq!(state(&app), TestState::B);
+
+    update_until_idle(&mut app);
+    assert_eq!(state(&app), TestState::B);
+}
+
+fn fade_out(millis: u64) -> TransitionOptions {
+    TransitionOptions {
+        fade_out: Some(Duration::from_millis(millis)),
+        ..Default::default()
+    }
+}
+
+fn routed_fade_out(routes: &TransitionRoutes<TestState>, from: TestState, to: TestState) -> u64 {
+    let options = routes.get(&from, &to).expect("no route matched");
+    options.fade_out.unwrap().as_millis() as u64
+}
+
+#[test]
+fn most_specific_route_wins() {
+    let mut routes = TransitionRoutes::default()
+        .with_default(fade_out(1))
+        .with_route(None, TestState::B, fade_out(2))
+        .with_route(TestState::A, None, fade_out(3))
+        .with_route(TestState::A, TestState::B, fade_out(4));
+
+    assert_eq!(routed_fade_out(&routes, TestState::A, TestState::B), 4);
+    assert_eq!(routed_fade_out(&routes, TestState::C, TestState::B), 2);
+    assert_eq!(routed_fade_out(&routes, TestState::A, TestState::C), 3);
+    assert_eq!(routed_fade_out(&routes, TestState::C, TestState::A), 1);
+
+    // any source into `to` is more specific than `from` into anything
+    routes.remove(TestState::A, TestState::B);
+    assert_eq!(routed_fade_out(&routes, TestState::A, TestState::B), 2);
+
+    // a route with neither side set matches everything before the default
+    routes.insert(None, None, fade_out(5));
+    assert_eq!(routed_fade_out(&routes, TestState::C, TestState::A), 5);
+    assert_eq!(routed_fade_out(&routes, TestState::A, TestState::C), 3);
+
+    routes.remove(None, None);
+    routes.set_default(None);
+    assert!(routes.get(&TestState::C, &TestState::A).is_none());
+}
+
+#[test]
+fn route_options_apply_to_the_transition() {
+    let routes = TransitionRoutes::default().with_route(TestState::A, TestState::B, fade_out(700));
+    let mut app = app(plugin().with_routes(routes));
+
+    transition(&mut app, |transition| {
+        transition.to(TestState::B);
+        let options = transition.status().options();
+        assert_eq!(options.fade_out, Some(Duration::from_millis(700)));
+    });
+}
+
+const PROFILE: &str = r##"(
+    styles: {
+        "slow": (effect: "fade", duration_ms: 1000, fade_in_ms: 300),
+        "flash": (color: "#ffffff", hold_ms: 100, easing: QuadraticOut),
+        "quick": (fade_out_ms: 50),
+    },
+    routes: [
+        (from: "A", to: "B", style: "slow"),
+        (to: "C", style: "flash"),
+    ],
+    default: "quick",
+)"##;
+
+fn millis(duration: Option<Duration>) -> Option<u64> {
+    duration.map(|duration| duration.as_millis() as u64)
+}
+
+#[test]
+fn profile_parses_styles_and_routes() {
+    let profile = TransitionProfile::parse(PROFILE.as_bytes()).unwrap();
+
+    let slow = profile.route(&TestState::A, &TestState::B).unwrap();
+    assert_eq!(
+        slow.effect.as_ref().and_then(SharedEffect::name),
+        Some("fade")
+    );
+    // the individual fade in takes precedence over `duration_ms`
+    assert_eq!(millis(slow.fade_out), Some(1000));
+    assert_eq!(millis(slow.fade_in), Some(300));
+
+    let flash = profile.route(&TestState::B, &TestState::C).unwrap();
+    assert_eq!(flash.color, Some(Color::from(Srgba::WHITE)));
+    assert_eq!(millis(flash.hold), Some(100));
+    assert!(flash.easing.is_some());
+
+    // the default is not a route
+    assert!(profile.route(&TestState::B, &TestState::A).is_none());
+    assert_eq!(millis(profile.default_style().unwrap().fade_out), Some(50));
+
+    // a route without `from` and `to` matches every transition
+    let profile = TransitionProfile::parse(
+        br#"(styles: { "quick": (fade_out_ms: 50) }, routes: [(style: "quick")])"#,
+    )
+    .unwrap();
+    let quick = profile.route(&TestState::B, &TestState::A).unwrap();
+    assert_eq!(millis(quick.fade_out), Some(50));
+}
+
+#[test]
+fn profile_rejects_unknown_styles() {
+    let result = TransitionProfile::parse(br#"(routes: [(to: "B", style: "missing")])"#);
+    assert!(
+        matches!(result, Err(TransitionProfileError::UnknownStyle(style)) if style == "missing")
+    );
+
+    let result = TransitionProfile::parse(br#"(default: "missing")"#);
+    assert!(matches!(
+        result,
+        Err(TransitionProfileError::UnknownStyle(_))
+    ));
+}
+
+#[test]
+fn profile_rejects_invalid_colors() {
+    let result = TransitionProfile::parse(br#"(styles: { "red": (color: "not a colour") })"#);
+    assert!(
+        matches!(result, Err(TransitionProfileError::Color(color, _)) if color == "not a colour")
+    );
+
+    // only the derive knows css names
+    let result = TransitionProfile::parse(br#"(styles: { "dark": (color: "black") })"#);
+    assert!(matches!(result, Err(TransitionProfileError::Color(_, _))));
+}
+
+fn profile_app(routes: TransitionRoutes<TestState>, profile: &str) -> App {
+    let mut app = app(plugin().with_routes(routes));
+    let profile = TransitionProfile::parse(profile.as_bytes()).unwrap();
+    let handle = app
+        .world_mut()
+        .get_resource_or_init::<Assets<TransitionProfile>>()
+        .add(profile);
+    app.insert_resource(TransitionProfileHandle::<TestState>::new(handle));
+    app
+}
+
+fn started_fade_out(app: &mut App, to: TestState) -> Option<u64> {
+    transition(app, |transition| {
+        transition.to(to);
+        millis(transition.status().options().fade_out)
+    })
+}
+
+#[test]
+fn profile_routes_win_only_when_as_specific() {
+    let routes = TransitionRoutes::default()
+        .with_default(fade_out(1))
+        .with_route(TestState::A, TestState::B, fade_out(2))
+        .with_route(TestState::A, TestState::C, fade_out(3));
+
+    // the profile's default and wildcard routes don't override an exact route from code
+    let mut app = profile_app(routes.clone(), PROFILE);
+    assert_eq!(started_fade_out(&mut app, TestState::C), Some(3));
+
+    // but its exact route does
+    let mut app = profile_app(routes.clone(), PROFILE);
+    assert_eq!(started_fade_out(&mut app, TestState::B), Some(1000));
+
+    // and its default wins over the code's when nothing else matches
+    let mut app = profile_app(
+        routes,
+        r#"(styles: { "quick": (fade_out_ms: 50) }, default: "quick")"#,
+    );
+    transition(&mut app, |transition| transition.to(TestState::B));
+    update_until_idle(&mut app);
+    assert_eq!(started_fade_out(&mut app, TestState::A), Some(50));
+}
+
+fn requests_under(policy: TransitionPolicy) -> App {
+    let mut app = app(plugin().with_policy(policy));
+    transition(&mut app, |transition| {
+        transition.to(TestState::B);
+        transition.to(TestState::C);
+        transition.to(TestState::A);
+    });
+    app
+}
+
+#[test]
+fn queue_plays_every_request() {
+    let mut app = requests_under(TransitionPolicy::Queue);
+    let queued = app
+        .world()
+        .resource::<PendingState<TestState>>()
+        .queued()
+        .count();
+    assert_eq!(queued, 2);
+
+    // the next request starts in the frame the previous one finishes
+    update_until_idle(&mut app);
+
+    assert_eq!(
+        log(&app),
+        [
+            "started A B",
+            "entered B",
+            "revealing",
+            "finished A B",
+            "started B C",
+            "entered C",
+            "revealing",
+            "finished B C",
+            "started C A",
+            "entered A",
+            "revealing",
+            "finished C A",
+        ]
+    );
+}
+
+#[test]
+fn replace_latest_plays_the_last_request() {
+    let mut app = requests_under(TransitionPolicy::ReplaceLatest);
+    let queued = app.world().resource::<PendingState<TestState>>();
+    assert_eq!(
+        queued
+            .queued()
+            .map(|request| &request.state)
+            .collect::<Vec<_>>(),
+        [&TestState::A]
+    );
+
+    update_until_idle(&mut app);
+
+    assert_eq!(
+        log(&app),
+        [
+            "started A B",
+            "entered B",
+            "revealing",
+            "finished A B",
+            "started B A",
+            "entered A",
+            "revealing",
+            "finished B A",
+        ]
+    );
+}
+
+#[test]
+fn ignore_while_busy_drops_requests() {
+    let mut app = requests_under(TransitionPolicy::IgnoreWhileBusy);
+    update_until_idle(&mut app);
+    assert_eq!(
+        log(&app),
+        ["started A B", "entered B", "revealing", "finished A B"]
+    );
+    assert_eq!(
+        app.world()
+            .resource::<PendingState<TestState>>()
+            .queued()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn interrupt_redirects_the_running_transition() {
+    let mut app = requests_under(TransitionPolicy::Interrupt);
+    update_until_idle(&mut app);
+
+    // the transition ends once even though it was replaced twice
+    assert_eq!(
+        log(&app),
+        [
+            "started A B",
+            "redirected B C",
+            "redirected C A",
+            "entered A",
+            "revealing",
+            "finished A A",
+        ]
+    );
+    assert_eq!(state(&app), TestState::A);
+}
+
+fn loading(app: &App) -> usize {
+    app.world()
+        .resource::<LoadingSet<TestState>>()
+        .handles()
+        .len()
+}
+
+#[test]
+fn interrupt_drops_the_previous_loading_set() {
+    let mut app = default_app();
+    let handle = Handle::<TransitionProfile>::default().untyped();
+    transition(&mut app, |transition| {
+        transition.to_loading(TestState::B, [handle.clone()]);
+    });
+    assert_eq!(loading(&app), 1);
+
+    transition(&mut app, |transition| {
+        transition.to_loading(TestState::C, [handle]);
+    });
+    assert_eq!(loading(&app), 1);
+
+    transition(&mut app, |transition| transition.to(TestState::A));
+    assert_eq!(loading(&app), 0);
+}
+
+fn update_until(app: &mut App, until: TransitionPhase) {
+    for _ in 0..50 {
+        if phase(app) == until {
+            return;
+        }
+        app.update();
+    }
+
+    panic!(
+        "transition never reached {until:?}, stuck in {:?}",
+        phase(app)
+    );
+}
+
+fn started(to: TestState) -> App {
+    let mut app = default_app();
+    transition(&mut app, |transition| transition.to(to));
+    app
+}
+
+#[test]
+fn cancel_reveals_without_changing_state() {
+    let mut app = started(TestState::B);
+    app.update();
+    assert_eq!(phase(&app), TransitionPhase::Covering);
+
+    assert!(transition(&mut app, |transition| transition.cancel()));
+    assert_eq!(phase(&app), TransitionPhase::Revealing);
+    update_until_idle(&mut app);
+
+    assert_eq!(state(&app), TestState::A);
+    assert_eq!(log(&app), ["started A B", "cancelled A B", "finished A A"]);
+}
+
+#[test]
+fn cancel_is_too_late_once_covered() {
+    for until in [TransitionPhase::Covered, TransitionPhase::Revealing] {
+        let mut app = started(TestState::B);
+        update_until(&mut app, until);
+
+        assert!(!transition(&mut app, |transition| transition.cancel()));
+        update_until_idle(&mut app);
+        assert_eq!(state(&app), TestState::B);
+    }
+
+    let mut app = default_app();
+    assert!(!transition(&mut app, |transition| transition.cancel()));
+}
+
+#[test]
+fn redirect_while_covering() {
+    let mut app = started(TestState::B);
+    app.update();
+
+    assert!(transition(&mut app, |transition| transition.redirect(TestState::C)));
+    update_until_idle(&mut app);
+
+    assert_eq!(state(&app), TestState::C);
+    assert_eq!(
+        log(&app),
+        [
+            "started A B",
+            "redirected B C",
+            "entered C",
+            "revealing",
+            "finished A C",
+        ]
+    );
+}
+
+#[test]
+fn redirect_while_covered_waits_for_the_new_state() {
+    let mut app = started(TestState::B);
+    update_until(&mut app, TransitionPhase::Covered);
+    assert_eq!(state(&app), TestState::B);
+
+    assert!(transition(&mut app, |transition| transition.redirect(TestState::C)));
+    for _ in 0..50 {
+        app.update();
+        if phase(&app) != TransitionPhase::Covered {
+            break;
+        }
+    }
+
+    // the stale event for B must not release the screen before C is entered
+    assert_eq!(state(&app), TestState::C);
+    update_until_idle(&mut app);
+    assert_eq!(
+        log(&app),
+        [
+            "started A B",
+            "entered B",
+            "redirected B C",
+            "entered C",
+            "revealing",
+            "finished A C",
+        ]
+    );
+}
+
+fn updates_while(app: &mut App, during: TransitionPhase) -> usize {
+    let mut updates = 0;
+    while phase(app) == during {
+        app.update();
+        updates += 1;
+        assert!(updates < 50, "transition stuck in {during:?}");
+    }
+    updates
+}
+
+#[test]
+fn redirect_while_covered_restarts_the_hold_and_loading() {
+    let mut app = app(plugin().with_hold(STEP * 4));
+    let handle = Handle::<TransitionProfile>::default().untyped();
+    transition(&mut app, |transition| {
+        transition.to_loading(TestState::B, [handle]);
+    });
+    update_until(&mut app, TransitionPhase::Covered);
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert!(transition(&mut app, |transition| transition.redirect(TestState::C)));
+    assert_eq!(loading(&app), 0);
+
+    // the full hold runs again for C
+    assert_eq!(updates_while(&mut app, TransitionPhase::Covered), 4);
+    assert_eq!(state(&app), TestState::C);
+}
+
+#[test]
+fn redirect_while_revealing_covers_again() {
+    let mut app = started(TestState::B);
+    update_until(&mut app, TransitionPhase::Revealing);
+
+    assert!(transition(&mut app, |transition| transition.redirect(TestState::C)));
+    assert_eq!(phase(&app), TransitionPhase::Covering);
+    update_until_idle(&mut app);
+
+    assert_eq!(state(&app), TestState::C);
+    assert_eq!(
+        log(&app),
+        [
+            "started A B",
+            "entered B",
+            "revealing",
+            "redirected B C",
+            "entered C",
+            "revealing",
+            "finished B C",
+        ]
+    );
+}
+
+#[test]
+fn redirect_without_a_transition_does_nothing() {
+    let mut app = default_app();
+    assert!(!transition(&mut app, |transition| transition.redirect(TestState::C)));
+    app.update();
+    assert_eq!(state(&app), TestState::A);
+    assert!(log(&app).is_empty());
+}
+
+#[test]
+fn transition_without_camera_keeps_its_duration() {
+    let mut app = app(plugin().with_overlay_fallback(OverlayFallback::AfterDuration));
+    transition(&mut app, |transition| transition.to(TestState::B));
+
+    // two updates per fade and one to notice the state was entered
+    assert_eq!(update_until_idle(&mut app), 5);
+    assert_eq!(state(&app), TestState::B);
+    assert_eq!(log(&app).last().map(String::as_str), Some("finished A B"));
+}
+
+#[test]
+fn transition_without_camera_can_skip_the_fades() {
+    let mut app = app(plugin().with_overlay_fallback(OverlayFallback::Immediate));
+    transition(&mut app, |transition| transition.to(TestState::B));
+
+    app.update();
+    assert_eq!(state(&app), TestState::B);
+    assert!(update_until_idle(&mut app) <= 2);
+    assert_eq!(log(&app).last().map(String::as_str), Some("finished A B"));
+}
+
+/// An app that loads assets from the crate's `assets` folder, where `missing.transitions.ron`
+/// does not exist. It has not been updated yet.
+fn asset_app(
+    plugin: TransitionsPlugin<TestState, TestCamera>,
+    setup: impl FnOnce(&mut App),
+) -> App {
+    let mut app = App::new();
+    app.add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
+        .init_asset::<TransitionProfile>()
+        .init_asset_loader::<TransitionProfileLoader>();
+    setup(&mut app);
+    app_with(app, plugin)
+}
+
+fn load_missing(asset_server: &AssetServer) -> UntypedHandle {
+    asset_server
+        .load::<TransitionProfile>("missing.transitions.ron")
+        .untyped()
+}
+
+fn update_until_load_fails(app: &mut App) {
+    // loading happens on the io task pool, so give it time instead of a number of updates
+    for _ in 0..1000 {
+        app.update();
+        if app.world().resource::<LoadingSet<TestState>>().is_empty() {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    panic!("the missing asset never failed to load");
+}
+
+#[test]
+fn failed_startup_assets_switch_to_the_error_state() {
+    let plugin = plugin()
+        .with_startup(StartupPolicy::CoveredUntilReady)
+        .with_error_state(TestState::C);
+    let mut app = asset_app(plugin, |app| {
+        app.add_systems(
+            OnEnter(TestState::A),
+            |asset_server: Res<AssetServer>, mut loading: ResMut<LoadingSet<TestState>>| {
+                loading.add(load_missing(&asset_server));
+            },
+        );
+    });
+
+    update_until_load_fails(&mut app);
+    update_until_idle(&mut app);
+
+    // the error state is entered before the startup cover reveals
+    assert_eq!(state(&app), TestState::C);
+    assert_eq!(log(&app), ["entered A", "failed A Some(C)", "entered C"]);
+}
+
+fn covered_on_b(app: &mut App) {
+    transition(app, |transition| transition.to(TestState::B));
+    update_until(app, TransitionPhase::Covered);
+}
+
+fn loading_progress(app: &App) -> f32 {
+    app.world()
+        .resource::<TransitionStatus<TestState>>()
+        .loading_progress()
+}
+
+#[test]
+fn a_live_hold_keeps_the_screen_covered() {
+    let mut app = default_app();
+    app.add_systems(OnEnter(TestState::B), |mut commands: Commands| {
+        commands.spawn(TransitionHold::<TestState>::default());
+    });
+    covered_on_b(&mut app);
+
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(phase(&app), TransitionPhase::Covered);
+
+    let hold = app
+        .world_mut()
+        .query_filtered::<Entity, With<TransitionHold<TestState>>>()
+        .single(app.world())
+        .unwrap();
+    app.world_mut().despawn(hold);
+    app.update();
+    assert_eq!(phase(&app), TransitionPhase::Revealing);
+}
+
+#[derive(Resource, Default)]
+struct Ready(bool);
+
+#[test]
+fn a_failing_condition_blocks_the_reveal() {
+    let mut app = default_app();
+    app.init_resource::<Ready>()
+        .add_transition_condition::<TestState, _>(|ready: Res<Ready>| ready.0);
+    covered_on_b(&mut app);
+
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(phase(&app), TransitionPhase::Covered);
+    // the hold is released, the condition is not
+    assert_eq!(loading_progress(&app), 0.5);
+
+    app.insert_resource(Ready(true));
+    app.update();
+    assert_eq!(phase(&app), TransitionPhase::Revealing);
+    assert_eq!(loading_progress(&app), 1.0);
+}
+
+#[test]
+fn the_minimum_hold_delays_the_reveal() {
+    let mut unheld = default_app();
+    covered_on_b(&mut unheld);
+    let unheld = updates_while(&mut unheld, TransitionPhase::Covered);
+
+    let mut held = app(plugin().with_hold(STEP * 3));
+    covered_on_b(&mut held);
+    let held = updates_while(&mut held, TransitionPhase::Covered);
+
+    // the update that notices the state was entered already counts towards the hold
+    assert_eq!(unheld, 1);
+    assert_eq!(held, 3);
+}
+
+fn loading_missing(error_state: TestState) -> App {
+    let mut app = asset_app(plugin().with_error_state(error_state), |_| {});
+    app.add_systems(
+        Update,
+        |mut messages: MessageReader<TransitionLoadFailed<TestState>>, mut log: ResMut<Log>| {
+            for message in messages.read() {
+                log.0.push(format!("message {:?}", message.fallback));
+            }
+        },
+    );
+    app.update();
+    clear_log(&mut app);
+
+    let handle = load_missing(app.world().resource::<AssetServer>());
+    transition(&mut app, |transition| {
+        transition.to_loading(TestState::B, [handle]);
+    });
+    app
+}
+
+// the message reader runs unordered with the plugin, so its entries are checked apart
+fn failure_log(app: &App) -> (Vec<String>, Vec<String>) {
+    log(app)
+        .into_iter()
+        .partition(|entry| entry.starts_with("message"))
+}
+
+#[test]
+fn failed_assets_switch_to_the_error_state() {
+    let mut app = loading_missing(TestState::C);
+    update_until_load_fails(&mut app);
+    update_until_idle(&mut app);
+
+    assert_eq!(state(&app), TestState::C);
+    let (messages, events) = failure_log(&app);
+    assert_eq!(messages, ["message Some(C)"]);
+    assert_eq!(
+        events,
+        [
+            "started A B",
+            "entered B",
+            "failed B Some(C)",
+            "entered C",
+            "revealing",
+            "finished A C",
+        ]
+    );
+}
+
+#[test]
+fn failed_assets_of_the_error_state_reveal_it() {
+    let mut app = loading_missing(TestState::B);
+    update_until_load_fails(&mut app);
+    update_until_idle(&mut app);
+
+    assert_eq!(state(&app), TestState::B);
+    let (messages, events) = failure_log(&app);
+    assert_eq!(messages, ["message None"]);
+    assert_eq!(
+        events,
+        [
+            "started A B",
+            "entered B",
+            "failed B None",
+            "revealing",
+            "finished A B"
+        ]
+    );
+}
+
+#[derive(Resource, Default)]
+struct Cleanups(usize);
+
+struct CountingEffect;
+
+impl TransitionEffect for CountingEffect {
+    fn spawn(&self, _content: &mut EntityWorldMut) {}
+
+    fn update(&self, _content: &mut EntityWorldMut, _context: &EffectContext) {}
+
+    fn cleanup(&self, content: &mut EntityWorldMut) {
+        content.world_scope(|world| world.resource_mut::<Cleanups>().0 += 1);
+    }
+}
+
+#[test]
+fn despawning_the_camera_cleans_up_the_effect() {
+    let mut app = app(plugin().with_effect(CountingEffect));
+    app.init_resource::<Cleanups>();
+    let camera = app.world_mut().spawn(TestCamera).id();
+    app.update();
+
+    app.world_mut().despawn(camera);
+    app.update();
+
+    assert_eq!(app.world().resource::<Cleanups>().0, 1);
+    assert!(
+        app.world_mut()
+            .query::<&FadeOverlay<TestState>>()
+            .iter(app.world())
+            .next()
+            .is_none()
+    );
+}
+
+/// Loading screen nodes changed in the last update.
+#[derive(Resource, Default)]
+struct ChangedNodes(usize);
+
+type LoadingScreenNode = Or<(With<LoadingScreenRoot<TestState>>, With<LoadingProgressBar>)>;
+
+fn count_changed_nodes(
+    q_nodes: Query<(), (Changed<Node>, LoadingScreenNode)>,
+    mut changed: ResMut<ChangedNodes>,
+) {
+    changed.0 = q_nodes.iter().count();
+}
+
+#[test]
+fn idle_loading_screens_are_left_unchanged() {
+    let mut app = app(plugin().with_loading_screen(|parent| {
+        parent.spawn((LoadingProgressBar, Node::default()));
+    }));
+    app.init_resource::<ChangedNodes>()
+        .add_systems(Last, count_changed_nodes);
+    app.world_mut().spawn(TestCamera);
+    app.update();
+    assert_eq!(app.world().resource::<ChangedNodes>().0, 2);
+
+    app.update();
+    assert_eq!(app.world().resource::<ChangedNodes>().0, 0);
+}
+
+fn idle_effect_changes(effect: impl TransitionEffect) -> [usize; 2] {
+    let mut app = app(plugin().with_effect(effect));
+    app.init_resource::<ChangedNodes>().add_systems(
+        Last,
+        |q_nodes: Query<(), (Changed<Node>, Without<FadeOverlay<TestState>>)>,
+         mut changed: ResMut<ChangedNodes>| changed.0 = q_nodes.iter().count(),
+    );
+    app.world_mut().spawn(TestCamera);
+    [(); 2].map(|_| {
+        app.update();
+        app.world().resource::<ChangedNodes>().0
+    })
+}
+
+#[test]
+fn an_idle_wipe_leaves_its_node_unchanged() {
+    assert_eq!(
+        idle_effect_changes(Wipe(WipeDirection::LeftToRight)),
+        [1, 0]
+    );
+}
+
+#[test]
+fn an_idle_iris_leaves_its_node_unchanged() {
+    assert_eq!(idle_effect_changes(Iris::default()), [1, 0]);
+}
+
+fn paused(clock: TransitionClock) -> App {
+    let mut app = app(plugin().with_clock(clock));
+    app.world_mut().resource_mut::<Time<Virtual>>().pause();
+    transition(&mut app, |transition| transition.to(TestState::B));
+    app
+}
+
+#[test]
+fn real_time_transitions_run_while_paused() {
+    let mut app = paused(TransitionClock::Real);
+    assert_eq!(update_until_idle(&mut app), 5);
+    assert_eq!(state(&app), TestState::B);
+}
+
+#[test]
+fn virtual_time_transitions_stop_while_paused() {
+    for clock in [TransitionClock::Virtual, TransitionClock::Time] {
+        let mut app = paused(clock);
+        for _ in 0..10 {
+            app.update();
+        }
+        assert_eq!(phase(&app), TransitionPhase::Covering);
+        assert_eq!(state(&app), TestState::A);
+
+        app.world_mut().resource_mut::<Time<Virtual>>().unpause();
+        update_until_idle(&mut app);
+        assert_eq!(state(&app), TestState::B);
+    }
+}
+
+#[test]
+fn frame_transitions_advance_a_step_per_update() {
+    // half a step per update, so each fade takes four updates whatever the time does
+    let mut app = paused(TransitionClock::Frames(STEP / 2));
+    assert_eq!(update_until_idle(&mut app), 9);
+}
+
+/// Updates each run condition let through, and the transition messages read next to them.
+#[derive(Resource, Default, Debug, PartialEq)]
+struct Gated {
+    in_transition: usize,
+    covering: usize,
+    revealing: usize,
+    to_b: usize,
+    messages: usize,
+}
+
+#[test]
+fn run_conditions_follow_the_transition_without_consuming_messages() {
+    let mut app = default_app();
+    app.init_resource::<Gated>().add_systems(
+        PostUpdate,
+        (
+            (|mut gated: ResMut<Gated>| gated.in_transition += 1)
+                .run_if(in_transition::<TestState>()),
+            (|mut gated: ResMut<Gated>| gated.covering += 1)
+                .run_if(transition_covering::<TestState>()),
+            (|mut gated: ResMut<Gated>| gated.revealing += 1)
+                .run_if(transition_revealing::<TestState>()),
+            (|mut gated: ResMut<Gated>| gated.to_b += 1).run_if(transitioning_to(TestState::B)),
+            |mut messages: MessageReader<TransitionMessage<TestState>>,
+             mut gated: ResMut<Gated>| gated.messages += messages.read().count(),
+        ),
+    );
+
+    transition(&mut app, |transition| transition.to(TestState::B));
+    assert_eq!(update_until_idle(&mut app), 5);
+    assert_eq!(state(&app), TestState::B);
+
+    // covering, covered, and two revealing updates before the one that finishes
+    assert_eq!(
+        *app.world().resource::<Gated>(),
+        Gated {
+            in_transition: 4,
+            covering: 1,
+            revealing: 2,
+            to_b: 4,
+            messages: 1,
+        }
+    );
+}
@@ -0,0 +1,67 @@
+use bevy_color::{Color, Srgba, palettes::css};
+use bevy_reflect::prelude::*;
+use bevy_state::prelude::*;
+use bevy_transitions::{SharedEffect, TransitionOptions, TransitionStates};
+use std::time::Duration;
+
+#[derive(States, TransitionStates, Reflect, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[transition(effect = "fade", duration_ms = 400, color = "black")]
+enum GameState {
+    #[default]
+    Menu,
+    #[transition(duration_ms = 1000, fade_in_ms = 250, easing = "QuadraticOut")]
+    #[transition(from = Paused, effect = "wipe_left_to_right", color = "#ff000080")]
+    Playing,
+    Paused,
+}
+
+fn route(from: GameState, to: GameState) -> TransitionOptions {
+    GameState::transition_routes()
+        .get(&from, &to)
+        .cloned()
+        .expect("no route matched")
+}
+
+fn effect(options: &TransitionOptions) -> Option<&str> {
+    options.effect.as_ref().and_then(SharedEffect::name)
+}
+
+fn millis(duration: Option<Duration>) -> Option<u64> {
+    duration.map(|duration| duration.as_millis() as u64)
+}
+
+#[test]
+fn variant_routes_apply_into_the_variant() {
+    let options = route(GameState::Menu, GameState::Playing);
+
+    // an individual fade takes precedence over `duration_ms`
+    assert_eq!(millis(options.fade_out), Some(1000));
+    assert_eq!(millis(options.fade_in), Some(250));
+    assert!(options.easing.is_some());
+
+    // variant routes don't inherit from the default
+    assert_eq!(effect(&options), None);
+    assert_eq!(options.color, None);
+}
+
+#[test]
+fn from_routes_win_over_the_variant_route() {
+    let options = route(GameState::Paused, GameState::Playing);
+
+    assert_eq!(effect(&options), Some("wipe_left_to_right"));
+    assert_eq!(
+        options.color,
+        Some(Color::from(Srgba::hex("#ff000080").unwrap()))
+    );
+    assert_eq!(options.fade_out, None);
+}
+
+#[test]
+fn enum_attribute_sets_the_default() {
+    let options = route(GameState::Playing, GameState::Paused);
+
+    assert_eq!(effect(&options), Some("fade"));
+    assert_eq!(options.color, Some(Color::from(css::BLACK)));
+    assert_eq!(millis(options.fade_out), Some(400));
+    assert_eq!(millis(options.fade_in), Some(400));
+}